@@ -0,0 +1,181 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// Generates the identifiers of the blank nodes of one parse.
+/// A new generator is created for every parse, see `ParserOptions::blank_node_ids`.
+pub trait BlankNodeIdGenerator {
+    fn next_id(&mut self) -> String;
+}
+
+/// 1, 2, 3, ...
+#[derive(Debug, Default, Clone)]
+pub struct SequentialIdGenerator {
+    counter: u64,
+}
+
+/// UUIDv7 without hyphens. This is the default.
+#[derive(Debug, Default, Clone)]
+pub struct UuidV7IdGenerator;
+
+/// {prefix}1, {prefix}2, {prefix}3, ...
+#[derive(Debug, Default, Clone)]
+pub struct PrefixedIdGenerator {
+    prefix: String,
+    counter: SequentialIdGenerator,
+}
+
+impl BlankNodeIdGenerator for SequentialIdGenerator {
+    fn next_id(&mut self) -> String {
+        self.counter += 1;
+        self.counter.to_string()
+    }
+}
+
+impl BlankNodeIdGenerator for UuidV7IdGenerator {
+    fn next_id(&mut self) -> String {
+        uuid::Uuid::now_v7().simple().to_string()
+    }
+}
+
+impl PrefixedIdGenerator {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        PrefixedIdGenerator {
+            prefix: prefix.into(),
+            counter: SequentialIdGenerator::default(),
+        }
+    }
+}
+
+impl BlankNodeIdGenerator for PrefixedIdGenerator {
+    fn next_id(&mut self) -> String {
+        format!("{}{}", self.prefix, self.counter.next_id())
+    }
+}
+
+/// Blank nodes of one parse. A label of the document (`_:label`) is kept unless the generator
+/// already issued it, and the generator never issues a label of the document,
+/// so that two distinct nodes are never merged.
+pub(crate) struct BlankNodes {
+    generator: Box<dyn BlankNodeIdGenerator>,
+    /// Label of `[_:]`, a generated id when not provided.
+    empty_label: Option<String>,
+    labels: HashMap<String, String>,
+    used: HashSet<String>,
+}
+
+impl BlankNodes {
+    pub(crate) fn new(
+        generator: Box<dyn BlankNodeIdGenerator>,
+        empty_label: Option<String>,
+    ) -> Self {
+        BlankNodes {
+            generator,
+            empty_label,
+            labels: HashMap::new(),
+            used: HashSet::new(),
+        }
+    }
+
+    /// Identifier of a label of the document, the same for every occurrence.
+    pub(crate) fn label(&mut self, label: &str) -> String {
+        if let Some(id) = self.labels.get(label) {
+            return id.clone();
+        }
+        let id = match self.empty_label.as_deref().filter(|_| label.is_empty()) {
+            Some(empty_label) if !self.used.contains(empty_label) => empty_label.to_string(),
+            _ if !label.is_empty() && !self.used.contains(label) => label.to_string(),
+            _ => self.next_id(),
+        };
+        self.used.insert(id.clone());
+        self.labels.insert(label.to_string(), id.clone());
+        id
+    }
+}
+
+impl BlankNodeIdGenerator for BlankNodes {
+    fn next_id(&mut self) -> String {
+        loop {
+            let id = self.generator.next_id();
+            if self.used.insert(id.clone()) {
+                return id;
+            }
+        }
+    }
+}
+
+type NewGenerator = dyn Fn() -> Box<dyn BlankNodeIdGenerator> + Send + Sync;
+
+/// Creates a fresh generator for each parse.
+#[derive(Clone)]
+pub(crate) struct BlankNodeIdFactory(Arc<NewGenerator>);
+
+impl BlankNodeIdFactory {
+    pub(crate) fn new<G, F>(new_generator: F) -> Self
+    where
+        G: BlankNodeIdGenerator + 'static,
+        F: Fn() -> G + Send + Sync + 'static,
+    {
+        BlankNodeIdFactory(Arc::new(move || Box::new(new_generator())))
+    }
+
+    pub(crate) fn generator(&self) -> Box<dyn BlankNodeIdGenerator> {
+        (self.0)()
+    }
+}
+
+impl Default for BlankNodeIdFactory {
+    fn default() -> Self {
+        BlankNodeIdFactory::new(|| UuidV7IdGenerator)
+    }
+}
+
+impl Debug for BlankNodeIdFactory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BlankNodeIdFactory")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BlankNodeIdGenerator, BlankNodes, PrefixedIdGenerator, SequentialIdGenerator};
+
+    #[test]
+    fn test_generators() {
+        let mut sequential = SequentialIdGenerator::default();
+        assert_eq!("1", sequential.next_id());
+        assert_eq!("2", sequential.next_id());
+
+        let mut prefixed = PrefixedIdGenerator::new("b");
+        assert_eq!("b1", prefixed.next_id());
+        assert_eq!("b2", prefixed.next_id());
+    }
+
+    #[test]
+    fn test_document_labels() {
+        let mut blank_nodes = BlankNodes::new(Box::new(SequentialIdGenerator::default()), None);
+        assert_eq!("1", blank_nodes.next_id());
+        // the label was issued by the generator
+        let renamed = blank_nodes.label("1");
+        assert_eq!("2", renamed);
+        assert_eq!(renamed, blank_nodes.label("1"));
+        // the generator skips the labels of the document
+        assert_eq!("a", blank_nodes.label("a"));
+        assert_eq!("4", blank_nodes.label("4"));
+        assert_eq!("3", blank_nodes.next_id());
+        assert_eq!("5", blank_nodes.next_id());
+        // [_:] is one node per parse
+        let empty = blank_nodes.label("");
+        assert_eq!("6", empty);
+        assert_eq!(empty, blank_nodes.label(""));
+
+        let mut blank_nodes = BlankNodes::new(
+            Box::new(SequentialIdGenerator::default()),
+            Some("empty".into()),
+        );
+        assert_eq!("empty", blank_nodes.label(""));
+        assert_eq!("empty", blank_nodes.label(""));
+    }
+}
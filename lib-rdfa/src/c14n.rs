@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use crate::Element;
+
+/// A node of the content of an element.
+#[derive(Debug, Clone, Copy)]
+pub enum XmlNode<'a, E> {
+    Element(E),
+    Text(&'a str),
+    Comment(&'a str),
+    /// Target and data.
+    ProcessingInstruction(&'a str, &'a str),
+}
+
+/// An attribute, namespace declarations excluded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XmlAttribute<'a> {
+    pub prefix: Option<&'a str>,
+    pub namespace: Option<&'a str>,
+    pub name: &'a str,
+    pub value: &'a str,
+}
+
+/// Namespaces by prefix, `None` being the default namespace.
+type Namespaces<'a> = BTreeMap<Option<&'a str>, &'a str>;
+
+/// Exclusive canonical XML with comments of the content of the element, as RDFa Core §7.5
+/// step 11 asks. An element only declares the namespaces it visibly utilizes, i.e the ones of
+/// its name and of its attributes, that an ancestor of the output does not declare yet
+/// (Exc-C14N §3): the top-level elements get the namespaces they use from the document,
+/// an unused namespace in scope is not rendered.
+pub(crate) fn xml_literal<'a, E: Element<'a>>(element: &E) -> String {
+    let mut out = String::new();
+    for node in element.nodes() {
+        write_node(node, &Namespaces::new(), &mut out);
+    }
+    out
+}
+
+fn write_node<'a, E: Element<'a>>(
+    node: XmlNode<'a, E>,
+    rendered: &Namespaces<'a>,
+    out: &mut String,
+) {
+    match node {
+        XmlNode::Element(element) => write_element(element, rendered, out),
+        XmlNode::Text(text) => escape(text, false, out),
+        XmlNode::Comment(comment) => {
+            out.push_str("<!--");
+            out.push_str(comment);
+            out.push_str("-->");
+        }
+        XmlNode::ProcessingInstruction(target, data) => {
+            out.push_str("<?");
+            out.push_str(target);
+            if !data.is_empty() {
+                out.push(' ');
+                out.push_str(data);
+            }
+            out.push_str("?>");
+        }
+    }
+}
+
+fn write_element<'a, E: Element<'a>>(element: E, rendered: &Namespaces<'a>, out: &mut String) {
+    let prefix = element.prefix();
+    let mut attributes = element.attributes();
+    attributes.sort_by_key(|a| (a.namespace.unwrap_or(""), a.name));
+
+    let mut utilized = Namespaces::new();
+    utilized.insert(prefix, element.namespace().unwrap_or(""));
+    for attribute in attributes.iter().filter(|a| a.prefix != Some("xml")) {
+        if let (Some(prefix), Some(namespace)) = (attribute.prefix, attribute.namespace) {
+            utilized.insert(Some(prefix), namespace);
+        }
+    }
+    let mut rendered = rendered.clone();
+    let name = qualified_name(prefix, element.name());
+    out.push('<');
+    out.push_str(&name);
+    for (prefix, namespace) in utilized {
+        // an absent default namespace is the empty one
+        if rendered.get(&prefix).copied().unwrap_or("") == namespace {
+            continue;
+        }
+        match prefix {
+            Some(prefix) => out.push_str(&format!(" xmlns:{prefix}=\"")),
+            None => out.push_str(" xmlns=\""),
+        }
+        escape(namespace, true, out);
+        out.push('"');
+        rendered.insert(prefix, namespace);
+    }
+    for attribute in attributes {
+        out.push(' ');
+        out.push_str(&qualified_name(attribute.prefix, attribute.name));
+        out.push_str("=\"");
+        escape(attribute.value, true, out);
+        out.push('"');
+    }
+    out.push('>');
+    for node in element.nodes() {
+        write_node(node, &rendered, out);
+    }
+    out.push_str("</");
+    out.push_str(&name);
+    out.push('>');
+}
+
+fn qualified_name(prefix: Option<&str>, name: &str) -> String {
+    match prefix {
+        Some(prefix) => format!("{prefix}:{name}"),
+        None => name.to_string(),
+    }
+}
+
+/// Character references of C14N §2.3 for text nodes and attribute values.
+fn escape(value: &str, in_attribute: bool, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' if !in_attribute => out.push_str("&gt;"),
+            '"' if in_attribute => out.push_str("&quot;"),
+            '\t' if in_attribute => out.push_str("&#x9;"),
+            '\n' if in_attribute => out.push_str("&#xA;"),
+            '\r' => out.push_str("&#xD;"),
+            c => out.push(c),
+        }
+    }
+}
@@ -1,26 +1,11 @@
 use std::{borrow::Cow, collections::HashMap};
 
-use crate::{Node, structs::DataTypeFromPattern};
+use crate::{
+    Node,
+    structs::DataTypeFromPattern,
+    term::{Iri, Term},
+};
 
-#[cfg(test)]
-static FAKE_UUID_GEN: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
-
-#[cfg(test)]
-pub(crate) fn reset_fake_uuid_gen() {
-    FAKE_UUID_GEN.store(0, std::sync::atomic::Ordering::SeqCst);
-}
-#[cfg(not(test))]
-pub fn get_uuid() -> String {
-    uuid::Uuid::now_v7().to_string().replace("-", "")
-}
-#[cfg(test)]
-pub fn get_uuid() -> String {
-    FAKE_UUID_GEN.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-    format!(
-        "{}",
-        FAKE_UUID_GEN.load(std::sync::atomic::Ordering::SeqCst)
-    )
-}
 // pub static DEFAULT_WELL_KNOWN_PREFIX: &str = "http://data.lblod.info/.well-known/genid#";
 #[allow(unused)]
 pub static RDFA_COPY_PREDICATE: &str = "http://www.w3.org/ns/rdfa#copy";
@@ -34,8 +19,70 @@ pub static NS_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
 pub static RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
 pub static RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
 pub static RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+pub static RDFA_ERROR: &str = "http://www.w3.org/ns/rdfa#Error";
+pub static RDFA_WARNING: &str = "http://www.w3.org/ns/rdfa#Warning";
+pub static RDFA_DOCUMENT_ERROR: &str = "http://www.w3.org/ns/rdfa#DocumentError";
+pub static RDFA_UNRESOLVED_CURIE: &str = "http://www.w3.org/ns/rdfa#UnresolvedCURIE";
+pub static RDFA_UNRESOLVED_TERM: &str = "http://www.w3.org/ns/rdfa#UnresolvedTerm";
+pub static RDFA_PREFIX_REDEFINITION: &str = "http://www.w3.org/ns/rdfa#PrefixRedefinition";
+pub static RDFA_CONTEXT: &str = "http://www.w3.org/ns/rdfa#context";
+pub static DC_DESCRIPTION: &str = "http://purl.org/dc/terms/description";
+pub static PTR_XPATH_POINTER: &str = "http://www.w3.org/2009/pointers#XPathPointer";
+pub static PTR_EXPRESSION: &str = "http://www.w3.org/2009/pointers#expression";
+pub static RDFS_SUB_PROPERTY_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subPropertyOf";
+pub static RDFS_SUB_CLASS_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+pub static OWL_EQUIVALENT_PROPERTY: &str = "http://www.w3.org/2002/07/owl#equivalentProperty";
+pub static OWL_EQUIVALENT_CLASS: &str = "http://www.w3.org/2002/07/owl#equivalentClass";
+pub static MD_ITEM: &str = "http://www.w3.org/ns/md#item";
+pub static XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+pub static XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
 
-pub static RESERVED_KEYWORDS: [&str; 3] = ["license", "describedby", "role"];
+/// Terms of the W3C initial context.
+pub static INITIAL_TERMS: [(&str, &str); 3] = [
+    (
+        "describedby",
+        "http://www.w3.org/2007/05/powder-s#describedby",
+    ),
+    ("license", "http://www.w3.org/1999/xhtml/vocab#license"),
+    ("role", "http://www.w3.org/1999/xhtml/vocab#role"),
+];
+
+/// Terms of the XHTML vocabulary, https://www.w3.org/2011/rdfa-context/xhtml-rdfa-1.1
+pub static XHTML_TERMS: [(&str, &str); 27] = [
+    ("alternate", "http://www.w3.org/1999/xhtml/vocab#alternate"),
+    ("appendix", "http://www.w3.org/1999/xhtml/vocab#appendix"),
+    ("bookmark", "http://www.w3.org/1999/xhtml/vocab#bookmark"),
+    ("chapter", "http://www.w3.org/1999/xhtml/vocab#chapter"),
+    ("cite", "http://www.w3.org/1999/xhtml/vocab#cite"),
+    ("contents", "http://www.w3.org/1999/xhtml/vocab#contents"),
+    ("copyright", "http://www.w3.org/1999/xhtml/vocab#copyright"),
+    ("first", "http://www.w3.org/1999/xhtml/vocab#first"),
+    ("glossary", "http://www.w3.org/1999/xhtml/vocab#glossary"),
+    ("help", "http://www.w3.org/1999/xhtml/vocab#help"),
+    ("icon", "http://www.w3.org/1999/xhtml/vocab#icon"),
+    ("index", "http://www.w3.org/1999/xhtml/vocab#index"),
+    ("itsRules", "http://www.w3.org/1999/xhtml/vocab#itsRules"),
+    ("last", "http://www.w3.org/1999/xhtml/vocab#last"),
+    ("license", "http://www.w3.org/1999/xhtml/vocab#license"),
+    ("meta", "http://www.w3.org/1999/xhtml/vocab#meta"),
+    ("next", "http://www.w3.org/1999/xhtml/vocab#next"),
+    ("p3pv1", "http://www.w3.org/1999/xhtml/vocab#p3pv1"),
+    ("prev", "http://www.w3.org/1999/xhtml/vocab#prev"),
+    ("previous", "http://www.w3.org/1999/xhtml/vocab#previous"),
+    ("role", "http://www.w3.org/1999/xhtml/vocab#role"),
+    ("section", "http://www.w3.org/1999/xhtml/vocab#section"),
+    ("start", "http://www.w3.org/1999/xhtml/vocab#start"),
+    (
+        "stylesheet",
+        "http://www.w3.org/1999/xhtml/vocab#stylesheet",
+    ),
+    (
+        "subsection",
+        "http://www.w3.org/1999/xhtml/vocab#subsection",
+    ),
+    ("top", "http://www.w3.org/1999/xhtml/vocab#top"),
+    ("up", "http://www.w3.org/1999/xhtml/vocab#up"),
+];
 
 pub static DATETIME_TYPES: [&DataTypeFromPattern; 6] = [
     &DataTypeFromPattern {
@@ -65,17 +112,18 @@ pub static DATETIME_TYPES: [&DataTypeFromPattern; 6] = [
 ];
 
 lazy_static::lazy_static! {
-    pub static ref NODE_RDF_XML_LITERAL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_XML_LITERAL));
-    pub static ref NODE_RDF_PLAIN_LITERAL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_PLAIN_LITERAL));
-    pub static ref NODE_RDF_HTML_LITERAL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_HTML_LITERAL));
-    pub static ref NODE_RDF_FIRST: Node<'static> = Node::Iri(Cow::Borrowed(RDF_FIRST));
-    pub static ref NODE_RDF_REST: Node<'static> = Node::Iri(Cow::Borrowed(RDF_REST));
-    pub static ref NODE_RDF_NIL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_NIL));
-    pub static ref NODE_RDFA_USES_VOCABULARY: Node<'static> = Node::Iri(Cow::Borrowed(RDFA_USES_VOCABULARY));
-    pub static ref NODE_RDF_XSD_STRING: Node<'static> = Node::Iri(Cow::Borrowed(RDF_XSD_STRING));
-    pub static ref NODE_RDFA_PATTERN_TYPE: Node<'static> = Node::Iri(Cow::Borrowed(RDFA_PATTERN_TYPE));
-    pub static ref NODE_RDFA_COPY_PREDICATE: Node<'static> = Node::Iri(Cow::Borrowed(RDFA_COPY_PREDICATE));
-    pub static ref NODE_NS_TYPE: Node<'static>=Node::Iri(Cow::Borrowed(NS_TYPE));
+    pub(crate) static ref NODE_RDF_XML_LITERAL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_XML_LITERAL));
+    pub(crate) static ref NODE_RDF_PLAIN_LITERAL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_PLAIN_LITERAL));
+    pub(crate) static ref NODE_RDF_HTML_LITERAL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_HTML_LITERAL));
+    pub(crate) static ref NODE_RDF_FIRST: Node<'static> = Node::Iri(Cow::Borrowed(RDF_FIRST));
+    pub(crate) static ref NODE_RDF_REST: Node<'static> = Node::Iri(Cow::Borrowed(RDF_REST));
+    pub(crate) static ref NODE_RDF_NIL: Node<'static> = Node::Iri(Cow::Borrowed(RDF_NIL));
+    pub(crate) static ref NODE_RDFA_USES_VOCABULARY: Node<'static> = Node::Iri(Cow::Borrowed(RDFA_USES_VOCABULARY));
+    pub(crate) static ref NODE_RDF_XSD_STRING: Node<'static> = Node::Iri(Cow::Borrowed(RDF_XSD_STRING));
+    pub(crate) static ref NODE_RDFA_PATTERN_TYPE: Node<'static> = Node::Iri(Cow::Borrowed(RDFA_PATTERN_TYPE));
+    pub(crate) static ref NODE_RDFA_COPY_PREDICATE: Node<'static> = Node::Iri(Cow::Borrowed(RDFA_COPY_PREDICATE));
+    pub(crate) static ref NODE_NS_TYPE: Node<'static>=Node::Iri(Cow::Borrowed(NS_TYPE));
+    pub(crate) static ref TERM_NS_TYPE: Term<'static> = Term::Iri(Iri::new(NS_TYPE));
     pub static  ref COMMON_PREFIXES: HashMap<&'static str, &'static str> =
         HashMap::from([
             ("", "http://www.w3.org/1999/xhtml/vocab#"),
@@ -120,11 +168,6 @@ lazy_static::lazy_static! {
             ("v","http://rdf.data-vocabulary.org/#"),
             ("vcard","http://www.w3.org/2006/vcard/ns#"),
             ("schema","http://schema.org/"),
-            ("describedby","http://www.w3.org/2007/05/powder-s#describedby"),
-            ("license","http://www.w3.org/1999/xhtml/vocab#license"),
-            ("role","http://www.w3.org/1999/xhtml/vocab#role"),
-
-
         ]);
 }
 
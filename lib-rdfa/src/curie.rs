@@ -0,0 +1,127 @@
+use std::borrow::Cow;
+
+/// `prefix:reference` (RDFa Core 1.1 §6), the prefix is empty for the default prefix
+/// and `_` for blank nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Curie<'a> {
+    pub(crate) prefix: &'a str,
+    pub(crate) reference: &'a str,
+}
+
+impl<'a> Curie<'a> {
+    /// None when there is no `:` or the prefix is not an NCName.
+    /// A reference starting with `//` makes an IRI, e.g `http://example.org`.
+    pub(crate) fn parse(value: &'a str) -> Option<Self> {
+        let (prefix, reference) = value.split_once(':')?;
+        if !prefix.is_empty() && !is_ncname(prefix) || reference.starts_with("//") {
+            return None;
+        }
+        Some(Curie { prefix, reference })
+    }
+
+    pub(crate) fn is_blank_node(&self) -> bool {
+        self.prefix == "_"
+    }
+
+    /// Prefixes are case-insensitive.
+    pub(crate) fn normalized_prefix(&self) -> Cow<'a, str> {
+        lowercase(self.prefix)
+    }
+}
+
+/// The CURIE of `[curie]`, None when the value is not bracketed.
+/// Err for a missing `]` or brackets around something that is not a CURIE.
+pub(crate) fn parse_safe_curie(value: &str) -> Result<Option<Curie<'_>>, ()> {
+    let Some(inner) = value.strip_prefix('[') else {
+        return Ok(None);
+    };
+    inner
+        .strip_suffix(']')
+        .and_then(Curie::parse)
+        .map(Some)
+        .ok_or(())
+}
+
+pub(crate) fn is_safe_curie(value: &str) -> bool {
+    value.starts_with('[') && value.ends_with(']')
+}
+
+/// The prefix of a declaration such as `foaf:` in @prefix, lower-cased.
+/// None when it is not an NCName followed by `:`.
+pub(crate) fn parse_prefix_name(value: &str) -> Option<Cow<'_, str>> {
+    value.strip_suffix(':').and_then(prefix_name)
+}
+
+/// A prefix that can be declared, lower-cased. `_` is reserved for blank nodes.
+pub(crate) fn prefix_name(value: &str) -> Option<Cow<'_, str>> {
+    Some(value)
+        .filter(|prefix| is_ncname(prefix) && *prefix != "_")
+        .map(lowercase)
+}
+
+pub(crate) fn lowercase(value: &str) -> Cow<'_, str> {
+    if value.chars().any(char::is_uppercase) {
+        Cow::Owned(value.to_lowercase())
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// A name without colon, Namespaces in XML 1.0 §3.
+pub(crate) fn is_ncname(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(is_name_start_char) && chars.all(is_name_char)
+}
+
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        'A'..='Z' | '_' | 'a'..='z'
+        | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}'
+        | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}')
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c,
+            '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Curie, is_ncname, parse_prefix_name, parse_safe_curie, prefix_name};
+
+    #[test]
+    fn test_curie() {
+        let curie = |prefix, reference| Some(Curie { prefix, reference });
+        assert_eq!(curie("foaf", "name"), Curie::parse("foaf:name"));
+        assert_eq!(curie("", "next"), Curie::parse(":next"));
+        assert_eq!(curie("ex", ":a"), Curie::parse("ex::a"));
+        assert_eq!(curie("urn", "isbn:123"), Curie::parse("urn:isbn:123"));
+        assert_eq!(curie("_", ""), Curie::parse("_:"));
+        assert_eq!(None, Curie::parse("http://example.org/"));
+        assert_eq!(None, Curie::parse("2ex:a"));
+        assert_eq!(None, Curie::parse("name"));
+
+        assert_eq!(Ok(curie("foaf", "name")), parse_safe_curie("[foaf:name]"));
+        assert_eq!(Ok(None), parse_safe_curie("foaf:name"));
+        assert_eq!(Err(()), parse_safe_curie("[foaf:name"));
+        assert_eq!(Err(()), parse_safe_curie("[name]"));
+    }
+
+    #[test]
+    fn test_prefix_name() {
+        assert!(is_ncname("foaf"));
+        assert!(is_ncname("_x-1.é"));
+        assert!(!is_ncname(""));
+        assert!(!is_ncname("-x"));
+        assert!(!is_ncname("a:b"));
+        assert_eq!(Some("foaf".into()), parse_prefix_name("FOAF:"));
+        assert_eq!(None, parse_prefix_name("foaf"));
+        assert_eq!(None, parse_prefix_name(":"));
+        assert_eq!(None, parse_prefix_name("foaf:name"));
+        assert_eq!(None, parse_prefix_name("_:"));
+        assert_eq!(Some("dc".into()), prefix_name("DC"));
+    }
+}
@@ -0,0 +1,277 @@
+use scraper::{ElementRef, Node};
+
+use crate::c14n::{XmlAttribute, XmlNode};
+
+/// An element of the document the RDFa attributes are read from:
+/// a `scraper::ElementRef` for html, a `roxmltree::Node` for xml.
+/// Sealed: the hidden methods, used to write XML literals, are internal.
+pub trait Element<'a>: Copy + sealed::Sealed {
+    /// Local name, e.g `div`.
+    fn name(&self) -> &'a str;
+    /// Value of an attribute, e.g `about` or `xml:lang`.
+    fn attr(&self, name: &str) -> Option<&'a str>;
+    /// Namespace declarations count, they bring prefixes in scope.
+    fn has_attributes(&self) -> bool;
+    /// Child elements, in document order.
+    fn children(&self) -> Vec<Self>;
+    /// This element and the ones below it, in document order.
+    fn descendants(&self) -> Vec<Self>;
+    /// Text nodes below this element, in document order.
+    fn texts(&self) -> Vec<&'a str>;
+    /// Markup of the children, for HTML literals.
+    fn inner_html(&self) -> String;
+    /// Prefixes declared with `xmlns:` on this element.
+    fn namespaces(&self) -> Vec<(&'a str, &'a str)>;
+    /// Namespace IRI of the element, if any.
+    #[doc(hidden)]
+    fn namespace(&self) -> Option<&'a str>;
+    #[doc(hidden)]
+    fn prefix(&self) -> Option<&'a str>;
+    #[doc(hidden)]
+    fn attributes(&self) -> Vec<XmlAttribute<'a>>;
+    /// Child nodes, for XML literals.
+    #[doc(hidden)]
+    fn nodes(&self) -> Vec<XmlNode<'a, Self>>;
+    /// e.g /html[1]/body[1]/div[2]
+    fn xpath(&self) -> String;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for scraper::ElementRef<'_> {}
+    impl Sealed for roxmltree::Node<'_, '_> {}
+}
+
+impl<'a> Element<'a> for ElementRef<'a> {
+    fn name(&self) -> &'a str {
+        self.value().name()
+    }
+
+    fn attr(&self, name: &str) -> Option<&'a str> {
+        self.value().attr(name)
+    }
+
+    fn has_attributes(&self) -> bool {
+        self.value().attrs().next().is_some()
+    }
+
+    fn children(&self) -> Vec<Self> {
+        ego_tree::NodeRef::children(self)
+            .filter_map(ElementRef::wrap)
+            .collect()
+    }
+
+    fn descendants(&self) -> Vec<Self> {
+        ego_tree::NodeRef::descendants(self)
+            .filter_map(ElementRef::wrap)
+            .collect()
+    }
+
+    fn texts(&self) -> Vec<&'a str> {
+        self.text().collect()
+    }
+
+    fn inner_html(&self) -> String {
+        ElementRef::inner_html(self)
+    }
+
+    fn namespaces(&self) -> Vec<(&'a str, &'a str)> {
+        self.value()
+            .attrs()
+            .filter_map(|(name, iri)| Some((name.strip_prefix("xmlns:")?, iri)))
+            .collect()
+    }
+
+    fn namespace(&self) -> Option<&'a str> {
+        let namespace: &'a str = &self.value().name.ns;
+        Some(namespace).filter(|ns| !ns.is_empty())
+    }
+
+    fn prefix(&self) -> Option<&'a str> {
+        self.value().name.prefix.as_deref()
+    }
+
+    /// Attributes are not namespaced in html, apart from `xml:`.
+    fn attributes(&self) -> Vec<XmlAttribute<'a>> {
+        self.value()
+            .attrs()
+            .filter(|(name, _)| *name != "xmlns" && !name.starts_with("xmlns:"))
+            .map(|(name, value)| match name.strip_prefix("xml:") {
+                Some(name) => XmlAttribute {
+                    prefix: Some("xml"),
+                    namespace: Some(roxmltree::NS_XML_URI),
+                    name,
+                    value,
+                },
+                None => XmlAttribute {
+                    prefix: None,
+                    namespace: None,
+                    name,
+                    value,
+                },
+            })
+            .collect()
+    }
+
+    fn nodes(&self) -> Vec<XmlNode<'a, Self>> {
+        ego_tree::NodeRef::children(self)
+            .filter_map(|node| match node.value() {
+                Node::Element(_) => ElementRef::wrap(node).map(XmlNode::Element),
+                Node::Text(text) => Some(XmlNode::Text(text)),
+                Node::Comment(comment) => Some(XmlNode::Comment(comment)),
+                Node::ProcessingInstruction(pi) => {
+                    Some(XmlNode::ProcessingInstruction(&pi.target, pi))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn xpath(&self) -> String {
+        xpath(
+            std::iter::once(**self)
+                .chain(self.ancestors())
+                .filter_map(ElementRef::wrap),
+            |e| {
+                e.prev_siblings()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|s| s.value().name() == e.value().name())
+                    .count()
+            },
+        )
+    }
+}
+
+impl<'a, 'input: 'a> Element<'a> for roxmltree::Node<'a, 'input> {
+    fn name(&self) -> &'a str {
+        self.tag_name().name()
+    }
+
+    /// Prefixed attributes are looked up by namespace, `xml:` is always bound.
+    fn attr(&self, name: &str) -> Option<&'a str> {
+        match name.split_once(':') {
+            Some(("xml", local)) => self.attribute((roxmltree::NS_XML_URI, local)),
+            Some((prefix, local)) => self
+                .lookup_namespace_uri(Some(prefix))
+                .and_then(|ns| self.attribute((ns, local))),
+            None => self.attribute(name),
+        }
+    }
+
+    fn has_attributes(&self) -> bool {
+        roxmltree::Node::attributes(self).next().is_some() || !Element::namespaces(self).is_empty()
+    }
+
+    fn children(&self) -> Vec<Self> {
+        roxmltree::Node::children(self)
+            .filter(|n| n.is_element())
+            .collect()
+    }
+
+    fn descendants(&self) -> Vec<Self> {
+        roxmltree::Node::descendants(self)
+            .filter(|n| n.is_element())
+            .collect()
+    }
+
+    fn texts(&self) -> Vec<&'a str> {
+        roxmltree::Node::descendants(self)
+            .filter(|n| n.is_text())
+            .filter_map(|n| n.text())
+            .collect()
+    }
+
+    /// The children as written in the document.
+    fn inner_html(&self) -> String {
+        match (self.first_child(), self.last_child()) {
+            (Some(first), Some(last)) => {
+                self.document().input_text()[first.range().start..last.range().end].to_string()
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// The namespaces in scope that are not in scope of the parent.
+    fn namespaces(&self) -> Vec<(&'a str, &'a str)> {
+        let inherited = self
+            .parent_element()
+            .map(|p| {
+                roxmltree::Node::namespaces(&p)
+                    .map(|ns| (ns.name(), ns.uri()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        roxmltree::Node::namespaces(self)
+            .filter(|ns| !inherited.contains(&(ns.name(), ns.uri())))
+            .filter_map(|ns| ns.name().map(|name| (name, ns.uri())))
+            .filter(|(name, _)| *name != "xml")
+            .collect()
+    }
+
+    fn namespace(&self) -> Option<&'a str> {
+        self.tag_name().namespace()
+    }
+
+    /// None when the namespace is also the default one.
+    fn prefix(&self) -> Option<&'a str> {
+        self.namespace()
+            .filter(|ns| self.lookup_namespace_uri(None) != Some(ns))
+            .and_then(|ns| prefix_of(self, ns))
+    }
+
+    fn attributes(&self) -> Vec<XmlAttribute<'a>> {
+        roxmltree::Node::attributes(self)
+            .map(|a| XmlAttribute {
+                prefix: a.namespace().and_then(|ns| prefix_of(self, ns)),
+                namespace: a.namespace(),
+                name: a.name(),
+                value: a.value(),
+            })
+            .collect()
+    }
+
+    fn nodes(&self) -> Vec<XmlNode<'a, Self>> {
+        roxmltree::Node::children(self)
+            .filter_map(|node| match node.node_type() {
+                roxmltree::NodeType::Element => Some(XmlNode::Element(node)),
+                roxmltree::NodeType::Text => node.text().map(XmlNode::Text),
+                roxmltree::NodeType::Comment => node.text().map(XmlNode::Comment),
+                roxmltree::NodeType::PI => node
+                    .pi()
+                    .map(|pi| XmlNode::ProcessingInstruction(pi.target, pi.value.unwrap_or(""))),
+                roxmltree::NodeType::Root => None,
+            })
+            .collect()
+    }
+
+    fn xpath(&self) -> String {
+        xpath(self.ancestors().filter(|n| n.is_element()), |e| {
+            e.prev_siblings()
+                .filter(|s| s.is_element() && s.tag_name() == e.tag_name())
+                .count()
+        })
+    }
+}
+
+/// A prefix bound to the namespace in scope of the node.
+fn prefix_of<'a>(node: &roxmltree::Node<'a, '_>, namespace: &str) -> Option<&'a str> {
+    if namespace == roxmltree::NS_XML_URI {
+        return Some("xml");
+    }
+    roxmltree::Node::namespaces(node)
+        .find(|ns| ns.uri() == namespace && ns.name().is_some())
+        .and_then(|ns| ns.name())
+}
+
+/// Steps from the element up to the root, with the count of preceding siblings of the same name.
+fn xpath<'a, E: Element<'a>>(
+    ancestors: impl Iterator<Item = E>,
+    preceding: impl Fn(&E) -> usize,
+) -> String {
+    let mut steps = ancestors
+        .map(|e| format!("{}[{}]", e.name(), preceding(&e) + 1))
+        .collect::<Vec<_>>();
+    steps.reverse();
+    format!("/{}", steps.join("/"))
+}
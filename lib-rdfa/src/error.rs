@@ -0,0 +1,185 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+/// Where in the document an error occurred.
+/// `element` and `attribute` are filled in by the traversal when known.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorLocation {
+    pub element: Option<String>,
+    pub attribute: Option<String>,
+    pub base: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RdfaError {
+    /// a CURIE whose prefix is not in scope
+    UnresolvedCurie {
+        value: String,
+        location: ErrorLocation,
+    },
+    /// a term that cannot be resolved without a vocab
+    UnresolvedTerm {
+        value: String,
+        location: ErrorLocation,
+    },
+    /// a value that is neither a CURIE nor a valid IRI
+    InvalidIri {
+        value: String,
+        reason: String,
+        location: ErrorLocation,
+    },
+    /// a SafeCURIE missing its closing bracket, or a value that is not a CURIE
+    InvalidSafeCurie {
+        value: String,
+        location: ErrorLocation,
+    },
+    /// a prefix declared twice in a @prefix attribute, or redefining one in scope
+    PrefixRedefinition {
+        value: String,
+        location: ErrorLocation,
+    },
+    /// a malformed @prefix declaration
+    InvalidPrefix {
+        value: String,
+        location: ErrorLocation,
+    },
+    /// the current subject could not be determined from the parent element
+    NoParentSubject { location: ErrorLocation },
+    /// an option rejected when building the processor
+    InvalidOption {
+        option: String,
+        value: String,
+        reason: String,
+    },
+    /// a document that is not well-formed xml
+    InvalidXml { reason: String },
+    /// an initial context document that cannot be read
+    InvalidInitialContext { reason: String },
+}
+
+impl ErrorLocation {
+    pub(crate) fn new(base: &str) -> Self {
+        ErrorLocation {
+            base: base.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RdfaError {
+    /// Attach the element name and the attribute the offending value comes from.
+    pub fn in_attribute(mut self, element: &str, attribute: &str) -> Self {
+        if let Some(location) = self.location_mut() {
+            location.element = Some(element.to_string());
+            location.attribute = Some(attribute.to_string());
+        }
+        self
+    }
+
+    /// Attach the element name.
+    pub fn in_element(mut self, element: &str) -> Self {
+        if let Some(location) = self.location_mut() {
+            location.element = Some(element.to_string());
+        }
+        self
+    }
+
+    pub fn location(&self) -> Option<&ErrorLocation> {
+        match self {
+            RdfaError::UnresolvedCurie { location, .. }
+            | RdfaError::UnresolvedTerm { location, .. }
+            | RdfaError::InvalidIri { location, .. }
+            | RdfaError::InvalidSafeCurie { location, .. }
+            | RdfaError::PrefixRedefinition { location, .. }
+            | RdfaError::InvalidPrefix { location, .. }
+            | RdfaError::NoParentSubject { location } => Some(location),
+            RdfaError::InvalidOption { .. }
+            | RdfaError::InvalidXml { .. }
+            | RdfaError::InvalidInitialContext { .. } => None,
+        }
+    }
+
+    /// The offending attribute value, if any.
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            RdfaError::UnresolvedCurie { value, .. }
+            | RdfaError::UnresolvedTerm { value, .. }
+            | RdfaError::InvalidIri { value, .. }
+            | RdfaError::InvalidSafeCurie { value, .. }
+            | RdfaError::PrefixRedefinition { value, .. }
+            | RdfaError::InvalidPrefix { value, .. }
+            | RdfaError::InvalidOption { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    fn location_mut(&mut self) -> Option<&mut ErrorLocation> {
+        match self {
+            RdfaError::UnresolvedCurie { location, .. }
+            | RdfaError::UnresolvedTerm { location, .. }
+            | RdfaError::InvalidIri { location, .. }
+            | RdfaError::InvalidSafeCurie { location, .. }
+            | RdfaError::PrefixRedefinition { location, .. }
+            | RdfaError::InvalidPrefix { location, .. }
+            | RdfaError::NoParentSubject { location } => Some(location),
+            RdfaError::InvalidOption { .. }
+            | RdfaError::InvalidXml { .. }
+            | RdfaError::InvalidInitialContext { .. } => None,
+        }
+    }
+}
+
+impl Display for ErrorLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(attribute) = &self.attribute {
+            write!(f, " in @{attribute}")?;
+        }
+        if let Some(element) = &self.element {
+            write!(f, " of <{element}>")?;
+        }
+        write!(f, " (base: {})", self.base)
+    }
+}
+
+impl Display for RdfaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RdfaError::UnresolvedCurie { value, location } => {
+                write!(f, "unresolved CURIE '{value}'{location}")
+            }
+            RdfaError::UnresolvedTerm { value, location } => {
+                write!(f, "unresolved term '{value}'{location}")
+            }
+            RdfaError::InvalidIri {
+                value,
+                reason,
+                location,
+            } => write!(f, "invalid IRI '{value}'{location}: {reason}"),
+            RdfaError::InvalidSafeCurie { value, location } => {
+                write!(f, "invalid SafeCURIE '{value}'{location}")
+            }
+            RdfaError::PrefixRedefinition { value, location } => {
+                write!(f, "prefix '{value}' redefined{location}")
+            }
+            RdfaError::InvalidPrefix { value, location } => {
+                write!(f, "invalid prefix declaration '{value}'{location}")
+            }
+            RdfaError::NoParentSubject { location } => {
+                write!(f, "could not determine parent subject{location}")
+            }
+            RdfaError::InvalidOption {
+                option,
+                value,
+                reason,
+            } => write!(f, "invalid option {option} '{value}': {reason}"),
+            RdfaError::InvalidXml { reason } => write!(f, "invalid xml: {reason}"),
+            RdfaError::InvalidInitialContext { reason } => {
+                write!(f, "invalid initial context: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for RdfaError {}
@@ -0,0 +1,357 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Formatter},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use log::debug;
+
+use crate::{
+    Element, RdfaProcessor, TripleSink,
+    constants::{
+        NS_TYPE, OWL_EQUIVALENT_CLASS, OWL_EQUIVALENT_PROPERTY, RDFA_COPY_PREDICATE,
+        RDFA_PATTERN_TYPE, RDFA_USES_VOCABULARY, RDFS_SUB_CLASS_OF, RDFS_SUB_PROPERTY_OF,
+    },
+    curie::{Curie, lowercase},
+    prefix_declarations,
+    structs::Context,
+    term::{Statement, Term},
+};
+
+/// Loads the vocabularies referenced by rdfa:usesVocabulary,
+/// see `ParserOptions::vocabulary_expansion`.
+pub trait VocabularyResolver: Send + Sync {
+    /// Triples of the vocabulary, `None` when it is unknown.
+    fn resolve(&self, vocab: &str) -> Option<Vec<Statement<'static>>>;
+}
+
+/// Vocabularies kept in memory, keyed by their IRI.
+impl VocabularyResolver for HashMap<String, Vec<Statement<'static>>> {
+    fn resolve(&self, vocab: &str) -> Option<Vec<Statement<'static>>> {
+        self.get(vocab).cloned()
+    }
+}
+
+/// Vocabularies published as RDFa documents in a directory,
+/// named after their IRI, see `DirectoryResolver::file_name`.
+#[derive(Debug, Clone)]
+pub struct DirectoryResolver {
+    dir: PathBuf,
+}
+
+impl DirectoryResolver {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        DirectoryResolver { dir: dir.into() }
+    }
+
+    /// Percent-encoded, bytes other than ascii letters, digits, `-`, `.` and `_` become `%XX`,
+    /// e.g `http://schema.org/` is read from `http%3A%2F%2Fschema.org%2F.html`.
+    pub fn file_name(vocab: &str) -> String {
+        let mut name = String::with_capacity(vocab.len() + 5);
+        for byte in vocab.bytes() {
+            match byte {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' => {
+                    name.push(byte as char)
+                }
+                byte => name.push_str(&format!("%{byte:02X}")),
+            }
+        }
+        name.push_str(".html");
+        name
+    }
+}
+
+impl VocabularyResolver for DirectoryResolver {
+    fn resolve(&self, vocab: &str) -> Option<Vec<Statement<'static>>> {
+        let path = self.dir.join(Self::file_name(vocab));
+        let html = std::fs::read_to_string(&path)
+            .inspect_err(|e| debug!("vocabulary {vocab} not found in {path:?}: {e}"))
+            .ok()?;
+        RdfaProcessor::builder()
+            .base(vocab)
+            .build()
+            .and_then(|processor| processor.parse_str(&html))
+            .inspect_err(|e| debug!("vocabulary {vocab} could not be parsed: {e}"))
+            .ok()
+            .map(|graph| graph.statements.into_iter().collect())
+    }
+}
+
+/// Resolver of the options.
+#[derive(Clone)]
+pub(crate) struct Vocabularies(pub(crate) Arc<dyn VocabularyResolver>);
+
+impl Debug for Vocabularies {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Vocabularies")
+    }
+}
+
+/// Expansion of the output graph once the document is processed:
+/// rdfa:copy is always applied, the entailment of the vocabularies
+/// only with a resolver (RDFa Core §10).
+///
+/// Patterns and references to them are held back, everything else is streamed to the
+/// inner sink. A pattern can be typed after the triples of its subject, so every triple
+/// is held until the end of the document when it may have patterns, see `may_have_patterns`.
+/// The triples entailed by the vocabularies are written last.
+pub(crate) struct ExpansionSink<'a, 's> {
+    sink: &'s mut dyn TripleSink<'a>,
+    held: Option<Vec<Statement<'a>>>,
+    patterns: HashSet<Term<'a>>,
+    pattern_triples: Vec<Statement<'a>>,
+    copies: Vec<Statement<'a>>,
+    /// only when the document may use a vocabulary, see `may_use_vocabulary`
+    resolver: Option<&'s dyn VocabularyResolver>,
+    /// the streamed output graph, when vocabularies are expanded
+    output: Vec<Statement<'a>>,
+}
+
+impl<'a, 's> ExpansionSink<'a, 's> {
+    pub(crate) fn new(
+        sink: &'s mut dyn TripleSink<'a>,
+        resolver: Option<&'s dyn VocabularyResolver>,
+        may_have_patterns: bool,
+    ) -> Self {
+        ExpansionSink {
+            sink,
+            held: may_have_patterns.then(Vec::new),
+            patterns: HashSet::new(),
+            pattern_triples: vec![],
+            copies: vec![],
+            resolver,
+            output: vec![],
+        }
+    }
+
+    pub(crate) fn finish(mut self) {
+        self.copy_patterns();
+        if let Some(resolver) = self.resolver {
+            for stmt in entailments(&self.output, resolver) {
+                self.sink.statement(stmt);
+            }
+        }
+    }
+
+    /// Copy the properties of referenced patterns to the subjects referencing them.
+    /// Patterns that are never referenced are kept as is.
+    fn copy_patterns(&mut self) {
+        let mut pattern_triples = std::mem::take(&mut self.pattern_triples);
+        for stmt in self.held.take().into_iter().flatten() {
+            if self.patterns.contains(&stmt.subject) {
+                pattern_triples.push(stmt);
+            } else {
+                self.emit(stmt);
+            }
+        }
+        let mut referenced = HashSet::new();
+        for copy in std::mem::take(&mut self.copies) {
+            if !self.patterns.contains(&copy.object) {
+                self.emit(copy);
+                continue;
+            }
+            for stmt in pattern_triples.iter().filter(|stmt| {
+                stmt.subject == copy.object && !stmt.object.is_iri(RDFA_PATTERN_TYPE)
+            }) {
+                self.emit(Statement {
+                    subject: copy.subject.clone(),
+                    predicate: stmt.predicate.clone(),
+                    object: stmt.object.clone(),
+                });
+            }
+            referenced.insert(copy.object);
+        }
+        for stmt in pattern_triples
+            .into_iter()
+            .filter(|stmt| !referenced.contains(&stmt.subject))
+        {
+            self.emit(stmt);
+        }
+    }
+
+    fn emit(&mut self, statement: Statement<'a>) {
+        if self.resolver.is_some() {
+            self.output.push(statement.clone());
+        }
+        self.sink.statement(statement);
+    }
+}
+
+impl<'a> TripleSink<'a> for ExpansionSink<'a, '_> {
+    fn triple(&mut self, subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) {
+        let statement = Statement {
+            subject,
+            predicate,
+            object,
+        };
+        if statement.predicate.is_iri(NS_TYPE) && statement.object.is_iri(RDFA_PATTERN_TYPE) {
+            self.patterns.insert(statement.subject.clone());
+            self.pattern_triples.push(statement);
+        } else if self.patterns.contains(&statement.subject) {
+            self.pattern_triples.push(statement);
+        } else if statement.predicate.is_iri(RDFA_COPY_PREDICATE) {
+            self.copies.push(statement);
+        } else if let Some(held) = self.held.as_mut() {
+            held.push(statement);
+        } else {
+            self.emit(statement);
+        }
+    }
+
+    fn prefix(&mut self, prefix: Cow<'a, str>, iri: Cow<'a, str>) {
+        self.sink.prefix(prefix, iri);
+    }
+
+    fn base(&mut self, base: Cow<'a, str>) {
+        self.sink.base(base);
+    }
+}
+
+/// Whether the document may type a rdfa:Pattern, with @typeof or as the object of rdf:type.
+/// Values are expanded with every prefix and vocabulary declared in the document, wherever
+/// they are in scope, so that an alias is found too,
+/// e.g `p:tern` with `p: http://www.w3.org/ns/rdfa#Pat`.
+/// False positives only hold the triples back until the end of the document.
+pub(crate) fn may_have_patterns<'a>(input: &impl Element<'a>, ctx: &Context<'a>) -> bool {
+    let elements = input.descendants();
+    let mut prefixes = ctx
+        .prefixes
+        .iter()
+        .map(|(prefix, iri)| (prefix.clone(), *iri))
+        .collect::<Vec<_>>();
+    let mut vocabs = ctx.vocab.iter().map(|v| v.as_ref()).collect::<Vec<_>>();
+    for element in &elements {
+        prefixes.extend(
+            element
+                .namespaces()
+                .into_iter()
+                .map(|(prefix, iri)| (lowercase(prefix), iri)),
+        );
+        if let Some(declarations) = element.attr("prefix") {
+            prefixes.extend(
+                prefix_declarations(declarations, &ctx.base)
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+        vocabs.extend(element.attr("vocab").map(str::trim));
+    }
+    let is_pattern = |value: &str| {
+        let value = value.trim_start_matches('[').trim_end_matches(']');
+        let expanded = match Curie::parse(value) {
+            Some(curie) => {
+                let prefix = curie.normalized_prefix();
+                prefixes
+                    .iter()
+                    .filter(|(p, _)| *p == prefix)
+                    .map(|(_, iri)| *iri)
+                    .chain(ctx.initial_context().get_prefix(&prefix))
+                    .any(|iri| [iri, curie.reference].concat() == RDFA_PATTERN_TYPE)
+            }
+            None => {
+                vocabs
+                    .iter()
+                    .any(|v| [v, value].concat() == RDFA_PATTERN_TYPE)
+                    || ctx.initial_context().get_term(value) == Some(RDFA_PATTERN_TYPE)
+            }
+        };
+        // IRIs, relative ones included
+        expanded || value.contains("Pattern")
+    };
+    elements.iter().any(|element| {
+        ["typeof", "resource", "href", "src"]
+            .iter()
+            .any(|attribute| {
+                element
+                    .attr(attribute)
+                    .is_some_and(|v| v.split_whitespace().any(is_pattern))
+            })
+    })
+}
+
+/// Whether an element may declare a vocabulary, only @vocab adds rdfa:usesVocabulary.
+pub(crate) fn may_use_vocabulary<'a>(element: &impl Element<'a>) -> bool {
+    element.attr("vocab").is_some_and(|v| !v.is_empty())
+        || element.children().iter().any(may_use_vocabulary)
+}
+
+/// Triples entailed by rdfs:subPropertyOf, rdfs:subClassOf, owl:equivalentProperty
+/// and owl:equivalentClass, applied to the output graph only.
+fn entailments<'a>(
+    output: &[Statement<'a>],
+    resolver: &dyn VocabularyResolver,
+) -> Vec<Statement<'a>> {
+    let vocabularies = output
+        .iter()
+        .filter(|stmt| stmt.predicate.is_iri(RDFA_USES_VOCABULARY))
+        .filter_map(|stmt| stmt.object.as_iri())
+        .map(|vocab| vocab.as_str().to_string())
+        .collect::<HashSet<_>>();
+    let vocabulary = vocabularies
+        .iter()
+        .filter_map(|vocab| {
+            let triples = resolver.resolve(vocab);
+            if triples.is_none() {
+                debug!("vocabulary {vocab} could not be resolved");
+            }
+            triples
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut super_properties = HashMap::<Term, Vec<Term>>::new();
+    let mut super_classes = HashMap::<Term, Vec<Term>>::new();
+    for stmt in vocabulary.iter().chain(output.iter()) {
+        let (subject, object) = (stmt.subject.clone(), stmt.object.clone());
+        let predicate = &stmt.predicate;
+        if predicate.is_iri(RDFS_SUB_PROPERTY_OF) {
+            super_properties.entry(subject).or_default().push(object);
+        } else if predicate.is_iri(OWL_EQUIVALENT_PROPERTY) {
+            super_properties
+                .entry(subject.clone())
+                .or_default()
+                .push(object.clone());
+            super_properties.entry(object).or_default().push(subject);
+        } else if predicate.is_iri(RDFS_SUB_CLASS_OF) {
+            super_classes.entry(subject).or_default().push(object);
+        } else if predicate.is_iri(OWL_EQUIVALENT_CLASS) {
+            super_classes
+                .entry(subject.clone())
+                .or_default()
+                .push(object.clone());
+            super_classes.entry(object).or_default().push(subject);
+        }
+    }
+
+    let mut graph = output.iter().cloned().collect::<HashSet<_>>();
+    let mut pending = output.to_vec();
+    let mut entailments = vec![];
+    while let Some(stmt) = pending.pop() {
+        let mut entailed = vec![];
+        for predicate in super_properties.get(&stmt.predicate).into_iter().flatten() {
+            entailed.push(Statement {
+                subject: stmt.subject.clone(),
+                predicate: predicate.clone(),
+                object: stmt.object.clone(),
+            });
+        }
+        if stmt.predicate.is_iri(NS_TYPE) {
+            for class in super_classes.get(&stmt.object).into_iter().flatten() {
+                entailed.push(Statement {
+                    subject: stmt.subject.clone(),
+                    predicate: stmt.predicate.clone(),
+                    object: class.clone(),
+                });
+            }
+        }
+        for stmt in entailed {
+            if graph.insert(stmt.clone()) {
+                entailments.push(stmt.clone());
+                pending.push(stmt);
+            }
+        }
+    }
+    entailments
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+use tortank::turtle::turtle_doc::{self, TurtleDoc};
+
+use crate::{
+    HostLanguage, RdfaError,
+    constants::{COMMON_PREFIXES, INITIAL_TERMS, XHTML_TERMS},
+};
+
+const RDFA_PREFIX: &str = "http://www.w3.org/ns/rdfa#prefix";
+const RDFA_TERM: &str = "http://www.w3.org/ns/rdfa#term";
+const RDFA_URI: &str = "http://www.w3.org/ns/rdfa#uri";
+
+lazy_static::lazy_static! {
+    pub(crate) static ref W3C_INITIAL_CONTEXT: InitialContext = InitialContext::w3c();
+    static ref XHTML_INITIAL_CONTEXT: InitialContext =
+        InitialContext::for_host_language(HostLanguage::Xhtml1);
+}
+
+/// The context of `for_host_language`, built once.
+pub(crate) fn host_language_context(host_language: HostLanguage) -> &'static InitialContext {
+    if host_language.has_xhtml_vocabulary() {
+        &XHTML_INITIAL_CONTEXT
+    } else {
+        &W3C_INITIAL_CONTEXT
+    }
+}
+
+/// Prefixes and terms in scope before any declaration of the document (RDFa Core §9).
+/// The W3C initial context of the host language by default.
+///
+/// ```rust
+/// use graph_rdfa_processor::{InitialContext, RdfaProcessor};
+/// let context = InitialContext::w3c()
+///     .prefix("besluit", "http://data.vlaanderen.be/ns/besluit#")
+///     .term("agenda", "http://data.vlaanderen.be/ns/besluit#behandelt");
+/// let processor = RdfaProcessor::builder()
+///     .base("http://example.com/")
+///     .initial_context(context)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitialContext {
+    prefixes: HashMap<String, String>,
+    terms: HashMap<String, String>,
+    /// Lower-cased terms, for the case-insensitive match.
+    lowercase_terms: HashMap<String, String>,
+}
+
+impl Default for InitialContext {
+    fn default() -> Self {
+        Self::w3c()
+    }
+}
+
+impl InitialContext {
+    /// https://www.w3.org/2011/rdfa-context/rdfa-1.1, shared by every host language.
+    pub fn w3c() -> Self {
+        let context = COMMON_PREFIXES
+            .iter()
+            .fold(Self::empty(), |context, (prefix, iri)| {
+                context.prefix(*prefix, *iri)
+            });
+        INITIAL_TERMS
+            .iter()
+            .fold(context, |context, (term, iri)| context.term(*term, *iri))
+    }
+
+    /// The W3C context extended by the host language, e.g with the terms
+    /// of the XHTML vocabulary (`next`, `stylesheet`...) for XHTML+RDFa 1.1 documents.
+    pub fn for_host_language(host_language: HostLanguage) -> Self {
+        let terms: &[(&str, &str)] = if host_language.has_xhtml_vocabulary() {
+            &XHTML_TERMS
+        } else {
+            &[]
+        };
+        terms.iter().fold(Self::w3c(), |context, (term, iri)| {
+            context.term(*term, *iri)
+        })
+    }
+
+    /// No prefix nor term, not even the default prefix: only what the document declares.
+    pub fn empty() -> Self {
+        InitialContext {
+            prefixes: HashMap::new(),
+            terms: HashMap::new(),
+            lowercase_terms: HashMap::new(),
+        }
+    }
+
+    /// Add or override a prefix, case-insensitive. The empty prefix is the default prefix (`:next`).
+    pub fn prefix(mut self, prefix: impl Into<String>, iri: impl Into<String>) -> Self {
+        self.prefixes
+            .insert(prefix.into().to_lowercase(), iri.into());
+        self
+    }
+
+    /// Add or override a term, e.g `license`.
+    pub fn term(mut self, term: impl Into<String>, iri: impl Into<String>) -> Self {
+        let (term, iri) = (term.into(), iri.into());
+        self.lowercase_terms
+            .insert(term.to_lowercase(), iri.clone());
+        self.terms.insert(term, iri);
+        self
+    }
+
+    /// Add the prefixes and terms of another context, overriding the ones already there.
+    pub fn extend(mut self, other: InitialContext) -> Self {
+        self.prefixes.extend(other.prefixes);
+        self.terms.extend(other.terms);
+        self.lowercase_terms.extend(other.lowercase_terms);
+        self
+    }
+
+    pub fn get_prefix(&self, prefix: &str) -> Option<&str> {
+        self.prefixes.get(prefix).map(String::as_str)
+    }
+
+    /// An exact match first, otherwise a case-insensitive one.
+    pub fn get_term(&self, term: &str) -> Option<&str> {
+        self.terms
+            .get(term)
+            .or_else(|| self.lowercase_terms.get(&term.to_lowercase()))
+            .map(String::as_str)
+    }
+
+    pub fn prefixes(&self) -> &HashMap<String, String> {
+        &self.prefixes
+    }
+
+    pub fn terms(&self) -> &HashMap<String, String> {
+        &self.terms
+    }
+
+    /// A context document in Turtle, made of `rdfa:prefix`/`rdfa:term` and `rdfa:uri` pairs:
+    ///
+    /// ```turtle
+    /// @prefix rdfa: <http://www.w3.org/ns/rdfa#> .
+    /// [] rdfa:prefix "besluit" ; rdfa:uri "http://data.vlaanderen.be/ns/besluit#" .
+    /// ```
+    pub fn from_turtle(turtle: &str) -> Result<Self, RdfaError> {
+        let document = TurtleDoc::try_from((turtle, None)).map_err(|e| invalid(e.message))?;
+        // declarations by subject, in document order
+        let mut subjects = vec![];
+        let mut nodes = HashMap::<String, Declaration>::new();
+        for stmt in document.list_statements(None, None, None) {
+            let (Ok(predicate), Some(value)) =
+                (stmt.predicate.get_iri(), turtle_value(&stmt.object))
+            else {
+                continue;
+            };
+            let subject = stmt.subject.to_string();
+            if !nodes.contains_key(&subject) {
+                subjects.push(subject.clone());
+            }
+            nodes.entry(subject).or_default().add(&predicate, value);
+        }
+        Self::from_declarations(subjects.iter().filter_map(|s| nodes.remove(s)))
+    }
+
+    /// A context document in JSON-LD. Only a subset is read, nothing is fetched:
+    /// - a node object, an array of node objects or an object with a `@graph` of them;
+    /// - an inline `@context` on the top-level object, mapping prefixes and terms
+    ///   to an IRI or to `{"@id": iri}`, so that keys can be terms, compact IRIs or IRIs;
+    /// - values are strings, `{"@value": value}`, `{"@id": iri}` or arrays of them.
+    ///
+    /// e.g `{"@context": {"rdfa": "http://www.w3.org/ns/rdfa#"}, "@graph": [{"rdfa:prefix": "besluit", "rdfa:uri": "http://data.vlaanderen.be/ns/besluit#"}]}`.
+    pub fn from_json_ld(json: &str) -> Result<Self, RdfaError> {
+        let document = serde_json::from_str::<Value>(json).map_err(|e| invalid(e.to_string()))?;
+        let no_context = Map::new();
+        let (context, nodes) = match &document {
+            Value::Array(nodes) => (&no_context, nodes.iter().collect()),
+            Value::Object(object) => {
+                let context = match object.get("@context") {
+                    Some(Value::Object(context)) => context,
+                    Some(_) => return Err(invalid("only an inline @context object is supported")),
+                    None => &no_context,
+                };
+                match object.get("@graph") {
+                    Some(Value::Array(nodes)) => (context, nodes.iter().collect()),
+                    Some(node) => (context, vec![node]),
+                    None => (context, vec![&document]),
+                }
+            }
+            _ => return Err(invalid("expected a JSON-LD object or array")),
+        };
+        Self::from_declarations(
+            nodes
+                .into_iter()
+                .filter_map(Value::as_object)
+                .map(|node| json_ld_declaration(node, context)),
+        )
+    }
+
+    fn from_declarations(nodes: impl Iterator<Item = Declaration>) -> Result<Self, RdfaError> {
+        let mut context = Self::empty();
+        for node in nodes {
+            if node.prefixes.is_empty() && node.terms.is_empty() {
+                continue;
+            }
+            let [uri] = node.uris.as_slice() else {
+                return Err(invalid(format!(
+                    "expected one rdfa:uri for {:?}, found {:?}",
+                    node.prefixes.iter().chain(&node.terms).collect::<Vec<_>>(),
+                    node.uris
+                )));
+            };
+            for prefix in node.prefixes {
+                context = context.prefix(prefix, uri);
+            }
+            for term in node.terms {
+                context = context.term(term, uri);
+            }
+        }
+        Ok(context)
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> RdfaError {
+    RdfaError::InvalidInitialContext {
+        reason: reason.into(),
+    }
+}
+
+/// The rdfa: properties of a node of a context document.
+#[derive(Debug, Default)]
+struct Declaration {
+    prefixes: Vec<String>,
+    terms: Vec<String>,
+    uris: Vec<String>,
+}
+
+impl Declaration {
+    fn add(&mut self, predicate: &str, value: String) {
+        match predicate {
+            RDFA_PREFIX => self.prefixes.push(value),
+            RDFA_TERM => self.terms.push(value),
+            RDFA_URI => self.uris.push(value),
+            _ => {}
+        }
+    }
+}
+
+/// The value of a literal or an IRI, None for a blank node.
+fn turtle_value(node: &turtle_doc::Node) -> Option<String> {
+    match node {
+        turtle_doc::Node::Iri(iri) => Some(iri.to_string()),
+        turtle_doc::Node::Literal(turtle_doc::Literal::Quoted { value, .. }) => {
+            Some(value.to_string())
+        }
+        turtle_doc::Node::Ref(node) => turtle_value(node),
+        _ => None,
+    }
+}
+
+/// A node object, its properties expanded with the context.
+fn json_ld_declaration(node: &Map<String, Value>, context: &Map<String, Value>) -> Declaration {
+    let mut declaration = Declaration::default();
+    for (key, value) in node.iter().filter(|(k, _)| !k.starts_with('@')) {
+        let predicate = expand_json_ld_iri(key, context);
+        for value in json_ld_values(value, context) {
+            declaration.add(&predicate, value);
+        }
+    }
+    declaration
+}
+
+fn json_ld_values(value: &Value, context: &Map<String, Value>) -> Vec<String> {
+    match value {
+        Value::String(value) => vec![value.clone()],
+        Value::Array(values) => values
+            .iter()
+            .flat_map(|v| json_ld_values(v, context))
+            .collect(),
+        Value::Object(object) => match (object.get("@value"), object.get("@id")) {
+            (Some(Value::String(value)), _) => vec![value.clone()],
+            (_, Some(Value::String(id))) => vec![expand_compact_iri(id, context)],
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// A term or compact IRI of the context, a full IRI as is.
+fn expand_json_ld_iri(key: &str, context: &Map<String, Value>) -> String {
+    let definition = context.get(key).and_then(|d| match d {
+        Value::String(iri) => Some(iri.as_str()),
+        Value::Object(d) => d.get("@id").and_then(Value::as_str),
+        _ => None,
+    });
+    if let Some(iri) = definition {
+        // a definition is not a term itself, this also avoids cycles
+        return expand_compact_iri(iri, context);
+    }
+    expand_compact_iri(key, context)
+}
+
+fn expand_compact_iri(key: &str, context: &Map<String, Value>) -> String {
+    if let Some((prefix, suffix)) = key.split_once(':')
+        && !suffix.starts_with("//")
+        && let Some(Value::String(namespace)) = context.get(prefix)
+    {
+        return format!("{namespace}{suffix}");
+    }
+    key.to_string()
+}
@@ -0,0 +1,173 @@
+use std::{borrow::Cow, fmt::Write};
+
+/// Components of an IRI reference, RFC 3986 §3.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Components<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Components<'a> {
+    /// Appendix B: `^(([^:/?#]+):)?(//([^/?#]*))?([^?#]*)(\?([^#]*))?(#(.*))?`
+    fn parse(reference: &'a str) -> Self {
+        let (rest, fragment) = match reference.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (reference, None),
+        };
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        let (scheme, rest) = match rest.split_once(':') {
+            Some((scheme, rest)) if is_scheme(scheme) => (Some(scheme), rest),
+            _ => (None, rest),
+        };
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let end = rest.find('/').unwrap_or(rest.len());
+                (Some(&rest[..end]), &rest[end..])
+            }
+            None => (None, rest),
+        };
+        Components {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
+
+    /// §5.3
+    fn recompose(&self, path: &str) -> String {
+        let mut iri = String::new();
+        if let Some(scheme) = self.scheme {
+            iri.push_str(scheme);
+            iri.push(':');
+        }
+        if let Some(authority) = self.authority {
+            iri.push_str("//");
+            iri.push_str(authority);
+        }
+        iri.push_str(path);
+        if let Some(query) = self.query {
+            iri.push('?');
+            iri.push_str(query);
+        }
+        if let Some(fragment) = self.fragment {
+            iri.push('#');
+            iri.push_str(fragment);
+        }
+        iri
+    }
+}
+
+/// Resolve a reference against an absolute base IRI, RFC 3986 §5.2.2.
+/// Characters are kept as written, an IRI is not turned into an URI.
+pub(crate) fn resolve(base: &str, reference: &str) -> String {
+    let base = Components::parse(base);
+    let r = Components::parse(reference);
+    let (target, path) = if r.scheme.is_some() {
+        (r, remove_dot_segments(r.path))
+    } else if r.authority.is_some() {
+        let target = Components {
+            scheme: base.scheme,
+            ..r
+        };
+        (target, remove_dot_segments(r.path))
+    } else if r.path.is_empty() {
+        let target = Components {
+            query: r.query.or(base.query),
+            fragment: r.fragment,
+            ..base
+        };
+        (target, base.path.to_string())
+    } else {
+        let path = if r.path.starts_with('/') {
+            remove_dot_segments(r.path)
+        } else {
+            remove_dot_segments(&merge(&base, r.path))
+        };
+        let target = Components {
+            query: r.query,
+            fragment: r.fragment,
+            ..base
+        };
+        (target, path)
+    };
+    target.recompose(&path)
+}
+
+/// §5.2.3
+fn merge(base: &Components, path: &str) -> String {
+    if base.authority.is_some() && base.path.is_empty() {
+        format!("/{path}")
+    } else {
+        let directory = base.path.rfind('/').map(|i| &base.path[..=i]).unwrap_or("");
+        format!("{directory}{path}")
+    }
+}
+
+/// §5.2.4
+fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output = String::with_capacity(path.len());
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            let last = output.rfind('/').unwrap_or(0);
+            output.truncate(last);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = usize::from(input.starts_with('/'));
+            let end = input[start..].find('/').map_or(input.len(), |i| i + start);
+            output.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output
+}
+
+/// `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )`
+fn is_scheme(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Percent-encode the characters that are not allowed in an IRI (RFC 3987 §2.2),
+/// e.g the space or the `{}` of a template, as a browser does.
+pub(crate) fn encode_disallowed(iri: &str) -> Cow<'_, str> {
+    if !iri.contains(is_disallowed) {
+        return Cow::Borrowed(iri);
+    }
+    let mut encoded = String::with_capacity(iri.len() + 8);
+    for c in iri.chars() {
+        if is_disallowed(c) {
+            let _ = write!(encoded, "%{:02X}", c as u32);
+        } else {
+            encoded.push(c);
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Not allowed in an IRIREF of N-Triples nor Turtle. Only ascii, so one byte.
+fn is_disallowed(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00}'..='\u{20}' | '\u{7F}' | '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\'
+    )
+}
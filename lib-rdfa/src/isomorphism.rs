@@ -0,0 +1,284 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{
+    structs::RdfaGraph,
+    term::{BlankNode, Statement, Term},
+};
+
+/// Triples that are only in one of two graphs, see `RdfaGraph::diff`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GraphDiff<'a> {
+    /// Only in the other graph.
+    pub added: Vec<Statement<'a>>,
+    /// Only in this graph.
+    pub removed: Vec<Statement<'a>>,
+}
+
+impl GraphDiff<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+type Mapping<'g> = HashMap<&'g str, &'g str>;
+
+impl<'a> RdfaGraph<'a> {
+    /// Same triples up to the ids of the blank nodes.
+    pub fn is_isomorphic(&self, other: &RdfaGraph) -> bool {
+        self.statements.len() == other.statements.len() && isomorphism(self, other).is_some()
+    }
+
+    /// Triples added and removed in `other`, blank nodes are matched by their surroundings.
+    /// Triples of blank nodes that cannot be matched are reported on both sides.
+    pub fn diff<'g>(&'g self, other: &'g RdfaGraph) -> GraphDiff<'g> {
+        if self.statements.len() == other.statements.len() && isomorphism(self, other).is_some() {
+            return GraphDiff::default();
+        }
+        let mapping = closest(Colored::new(self), Colored::new(other), self, other);
+        let mut added = missing(other, self, &inverse(&mapping));
+        let mut removed = missing(self, other, &mapping);
+        added.sort();
+        removed.sort();
+        GraphDiff { added, removed }
+    }
+}
+
+/// Statements of `graph` that are not in `other` once their blank nodes are mapped.
+fn missing<'g>(graph: &'g RdfaGraph, other: &RdfaGraph, mapping: &Mapping) -> Vec<Statement<'g>> {
+    graph
+        .statements
+        .iter()
+        .filter(|stmt| {
+            map_statement(stmt, mapping)
+                .filter(|stmt| other.statements.contains(stmt))
+                .is_none()
+        })
+        .cloned()
+        .collect()
+}
+
+fn map_statement<'g>(stmt: &Statement<'g>, mapping: &Mapping) -> Option<Statement<'g>> {
+    let map = |term: &Term<'g>| match term {
+        Term::BlankNode(bnode) => mapping
+            .get(bnode.id())
+            .map(|id| Term::BlankNode(BlankNode::new(id.to_string()))),
+        term => Some(term.clone()),
+    };
+    Some(Statement {
+        subject: map(&stmt.subject)?,
+        predicate: map(&stmt.predicate)?,
+        object: map(&stmt.object)?,
+    })
+}
+
+fn isomorphism<'g>(graph: &'g RdfaGraph, other: &'g RdfaGraph) -> Option<Mapping<'g>> {
+    search(Colored::new(graph), Colored::new(other), graph, other)
+}
+
+/// Refine the colors of the blank nodes, then try each candidate of the smallest
+/// ambiguous class until a mapping turns out to preserve every triple.
+fn search<'g>(
+    mut colored: Colored<'g>,
+    mut other_colored: Colored<'g>,
+    graph: &RdfaGraph,
+    other: &RdfaGraph,
+) -> Option<Mapping<'g>> {
+    refine(&mut colored, &mut other_colored);
+    if colored.histogram() != other_colored.histogram() {
+        return None;
+    }
+    let Some(color) = colored.ambiguous_color() else {
+        let mapping = colored.pairs(&other_colored);
+        return missing(graph, other, &mapping)
+            .is_empty()
+            .then_some(mapping);
+    };
+    let chosen = hash(&(color, "chosen"));
+    let bnode = colored.with_color(color)[0];
+    for candidate in other_colored.with_color(color) {
+        let (mut colored, mut other_colored) = (colored.clone(), other_colored.clone());
+        colored.colors.insert(bnode, chosen);
+        other_colored.colors.insert(candidate, chosen);
+        if let Some(mapping) = search(colored, other_colored, graph, other) {
+            return Some(mapping);
+        }
+    }
+    None
+}
+
+/// Pair the blank nodes of two graphs that are not isomorphic.
+/// Blank nodes sharing a color are told apart one at a time, keeping the candidate
+/// that leaves the fewest triples unmatched once the colors are refined again.
+fn closest<'g>(
+    mut colored: Colored<'g>,
+    mut other_colored: Colored<'g>,
+    graph: &'g RdfaGraph,
+    other: &'g RdfaGraph,
+) -> Mapping<'g> {
+    loop {
+        refine(&mut colored, &mut other_colored);
+        let Some(color) = colored.shared_ambiguous_color(&other_colored) else {
+            return colored.pairs(&other_colored);
+        };
+        let chosen = hash(&(color, "chosen"));
+        let bnode = colored.with_color(color)[0];
+        let best = other_colored
+            .with_color(color)
+            .into_iter()
+            .map(|candidate| {
+                let (mut colored, mut other_colored) = (colored.clone(), other_colored.clone());
+                colored.colors.insert(bnode, chosen);
+                other_colored.colors.insert(candidate, chosen);
+                refine(&mut colored, &mut other_colored);
+                let mapping = colored.pairs(&other_colored);
+                let unmatched = missing(graph, other, &mapping).len()
+                    + missing(other, graph, &inverse(&mapping)).len();
+                (unmatched, colored, other_colored)
+            })
+            .min_by_key(|(unmatched, _, _)| *unmatched);
+        let Some((_, best, other_best)) = best else {
+            return colored.pairs(&other_colored);
+        };
+        (colored, other_colored) = (best, other_best);
+    }
+}
+
+fn inverse<'g>(mapping: &Mapping<'g>) -> Mapping<'g> {
+    mapping.iter().map(|(k, v)| (*v, *k)).collect()
+}
+
+/// Blank nodes of a graph colored by the triples around them.
+#[derive(Clone)]
+struct Colored<'g> {
+    triples: Vec<&'g Statement<'g>>,
+    colors: HashMap<&'g str, u64>,
+}
+
+impl<'g> Colored<'g> {
+    fn new(graph: &'g RdfaGraph) -> Self {
+        let triples = graph.statements.iter().collect::<Vec<_>>();
+        let colors = triples
+            .iter()
+            .flat_map(|stmt| [&stmt.subject, &stmt.predicate, &stmt.object])
+            .filter_map(Term::as_blank_node)
+            .map(|bnode| (bnode.id(), 0))
+            .collect();
+        Colored { triples, colors }
+    }
+
+    fn label(&self, term: &Term) -> u64 {
+        match term {
+            Term::BlankNode(bnode) => self.colors[bnode.id()],
+            term => hash(term),
+        }
+    }
+
+    /// The new color of a blank node hashes its color with the triples it is part of.
+    fn refine(&mut self) {
+        let mut signatures = HashMap::<&str, Vec<u64>>::new();
+        for stmt in &self.triples {
+            let labels = [
+                self.label(&stmt.subject),
+                self.label(&stmt.predicate),
+                self.label(&stmt.object),
+            ];
+            for (position, term) in [&stmt.subject, &stmt.predicate, &stmt.object]
+                .into_iter()
+                .enumerate()
+            {
+                if let Term::BlankNode(bnode) = term {
+                    signatures
+                        .entry(bnode.id())
+                        .or_default()
+                        .push(hash(&(position, labels)));
+                }
+            }
+        }
+        for (bnode, mut signature) in signatures {
+            signature.sort_unstable();
+            let color = self.colors[bnode];
+            self.colors.insert(bnode, hash(&(color, signature)));
+        }
+    }
+
+    fn classes(&self) -> usize {
+        self.colors.values().collect::<HashSet<_>>().len()
+    }
+
+    fn histogram(&self) -> BTreeMap<u64, usize> {
+        let mut histogram = BTreeMap::new();
+        for color in self.colors.values() {
+            *histogram.entry(*color).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Color shared by the fewest blank nodes, if any is shared.
+    fn ambiguous_color(&self) -> Option<u64> {
+        self.histogram()
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .min_by_key(|(color, count)| (*count, *color))
+            .map(|(color, _)| color)
+    }
+
+    /// Color of both graphs shared by the fewest blank nodes, if any is shared on either side.
+    fn shared_ambiguous_color(&self, other: &Colored) -> Option<u64> {
+        let other_histogram = other.histogram();
+        self.histogram()
+            .into_iter()
+            .filter_map(|(color, count)| {
+                other_histogram
+                    .get(&color)
+                    .map(|other_count| (color, count.max(*other_count)))
+            })
+            .filter(|(_, count)| *count > 1)
+            .min_by_key(|(color, count)| (*count, *color))
+            .map(|(color, _)| color)
+    }
+
+    fn with_color(&self, color: u64) -> Vec<&'g str> {
+        let mut bnodes = self
+            .colors
+            .iter()
+            .filter(|(_, c)| **c == color)
+            .map(|(bnode, _)| *bnode)
+            .collect::<Vec<_>>();
+        bnodes.sort_unstable();
+        bnodes
+    }
+
+    /// Pair the blank nodes of both graphs by color.
+    fn pairs(&self, other: &Colored<'g>) -> Mapping<'g> {
+        let mut mapping = Mapping::new();
+        for color in self.histogram().into_keys() {
+            mapping.extend(
+                self.with_color(color)
+                    .into_iter()
+                    .zip(other.with_color(color)),
+            );
+        }
+        mapping
+    }
+}
+
+/// Refine both graphs the same number of times, until their partitions are stable.
+fn refine(colored: &mut Colored, other_colored: &mut Colored) {
+    loop {
+        let classes = (colored.classes(), other_colored.classes());
+        colored.refine();
+        other_colored.refine();
+        if classes == (colored.classes(), other_colored.classes()) {
+            return;
+        }
+    }
+}
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
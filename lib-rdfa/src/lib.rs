@@ -1,123 +1,222 @@
-use std::{borrow::Cow, collections::HashMap, error::Error, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
+mod blank_node;
+mod c14n;
 mod constants;
+mod curie;
+mod element;
+mod error;
+mod expansion;
+mod initial_context;
+mod iri;
+mod isomorphism;
+mod microdata;
+mod processor;
+mod processor_graph;
+mod query;
 mod rdfa_elt;
+mod serializer;
+mod sink;
 mod structs;
+mod term;
 #[cfg(test)]
 mod tests;
 
 use constants::{
-    COMMON_PREFIXES, IS_SPECIAL_NODE_FN, NODE_NS_TYPE, NODE_RDF_FIRST, NODE_RDF_NIL,
-    NODE_RDF_PLAIN_LITERAL, NODE_RDF_REST, NODE_RDFA_PATTERN_TYPE, NODE_RDFA_USES_VOCABULARY,
-    RESERVED_KEYWORDS, get_uuid,
+    IS_SPECIAL_NODE_FN, NODE_NS_TYPE, NODE_RDF_FIRST, NODE_RDF_NIL, NODE_RDF_PLAIN_LITERAL,
+    NODE_RDF_REST, NODE_RDF_XML_LITERAL, NODE_RDFA_USES_VOCABULARY,
 };
-use log::{debug, error};
-use rdfa_elt::RdfaElement;
-use scraper::{ElementRef, Selector};
-use url::{Origin, Url};
-
-use structs::{Context, DataTypeFromPattern, Literal, Node, Statement};
-
+use curie::{Curie, is_safe_curie, parse_prefix_name, parse_safe_curie, prefix_name};
+use expansion::ExpansionSink;
+use log::debug;
+use processor_graph::ProcessorGraph;
+use rdfa_elt::{RdfaElement, without_fragment};
+use url::Url;
+
+use structs::{Context, DataTypeFromPattern, Node, NodeStatement};
+
+use blank_node::BlankNodes;
+pub use blank_node::{
+    BlankNodeIdGenerator, PrefixedIdGenerator, SequentialIdGenerator, UuidV7IdGenerator,
+};
+pub use element::Element;
+pub use error::{ErrorLocation, RdfaError};
+pub use expansion::{DirectoryResolver, VocabularyResolver};
+pub use initial_context::InitialContext;
+pub use isomorphism::GraphDiff;
+pub use processor::{HostLanguage, ParserOptions, RdfaProcessor, RdfaVersion};
+pub use query::TripleIndex;
+pub use serializer::JsonLdForm;
+pub use sink::{NTriplesWriter, TripleCounter, TripleSink};
 pub use structs::RdfaGraph;
+pub use term::{BlankNode, Iri, Literal, Statement, Term};
 
-struct NodeContext<'a, 'b> {
-    element_ref: &'b ElementRef<'a>,
+struct NodeContext<'a, 'b, E> {
+    element: &'b E,
     ctx: Context<'a>,
-    stmts: &'b mut Vec<Statement<'a>>,
+    stmts: &'b mut dyn TripleSink<'a>,
     current_node: Node<'a>,
     rels: Option<Vec<Node<'a>>>,
     revs: Option<Vec<Node<'a>>>,
-    in_list_stmts: &'b mut Vec<Statement<'a>>,
+    in_list_stmts: &'b mut Vec<NodeStatement<'a>>,
     type_ofs: Option<Vec<Node<'a>>>,
     parent_in_rel: Option<Vec<Node<'a>>>,
     parent_in_rev: Option<Vec<Node<'a>>>,
     parent: &'b Option<&'b Context<'a>>,
+    processor_graph: &'b mut ProcessorGraph<'a>,
+    bnode_ids: &'b mut BlankNodes,
 }
 
 impl<'a> RdfaGraph<'a> {
-    pub fn parse(
-        input: &ElementRef<'a>,
+    pub(crate) fn parse(
+        input: &impl Element<'a>,
         initial_context: Context<'a>,
-    ) -> Result<RdfaGraph<'a>, Box<dyn Error>> {
-        let mut triples = vec![];
-        let mut inlist_triples = vec![];
-        let well_known_prefix = initial_context.well_known_prefix;
-        if initial_context.empty_ref_node_substitute.is_empty() {
-            return Err(
-                "if you provide a context, you most provide an empty_ref_node_substitute property."
-                    .into(),
-            );
-        }
-        traverse_element(
-            input,
-            None,
-            initial_context,
-            &mut triples,
-            &mut inlist_triples,
-        )?;
+        bnode_ids: &mut BlankNodes,
+    ) -> Result<RdfaGraph<'a>, RdfaError> {
+        let mut graph = RdfaGraph {
+            // resolved against the document by the traversal
+            base: initial_context.base.clone(),
+            well_known_prefix: initial_context.well_known_prefix.map(Cow::Borrowed),
+            statements: Default::default(),
+            // the prefixes of the document are added by the traversal
+            prefixes: initial_context
+                .prefixes
+                .iter()
+                .map(|(k, v)| (k.clone(), Cow::Borrowed(*v)))
+                .collect(),
+            vocab: document_vocab(input, &initial_context),
+            processor_graph: None,
+        };
+        let processor_graph = parse_into(input, initial_context, &mut graph, bnode_ids)?;
+        graph.processor_graph = processor_graph.map(Box::new);
+        Ok(graph)
+    }
 
-        // fixes examples/other/example0002.html
-        // when base ends with "/", inlist_triples is not append
-        // todo find a better fix
-        if !inlist_triples.is_empty() {
-            triples.append(&mut inlist_triples);
+    /// Shortcut for `ParserOptions` with only a base and a well known prefix.
+    pub fn parse_str(
+        html: &str,
+        base: &str,
+        well_known_prefix: Option<&str>,
+    ) -> Result<String, RdfaError> {
+        let mut options = RdfaProcessor::builder().base(base);
+        if let Some(well_known_prefix) = well_known_prefix.filter(|f| !f.is_empty()) {
+            options = options.well_known_prefix(well_known_prefix);
         }
+        Ok(options.build()?.parse_str(html)?.to_ntriples())
+    }
+}
 
-        triples = copy_pattern(triples)?;
-
-        Ok(RdfaGraph {
-            statements: triples.into_iter().collect(),
-            well_known_prefix,
-        })
+/// Stream the triples of the document to the sink.
+/// Returns the processor graph when enabled.
+pub(crate) fn parse_into<'a>(
+    input: &impl Element<'a>,
+    initial_context: Context<'a>,
+    sink: &mut dyn TripleSink<'a>,
+    bnode_ids: &mut BlankNodes,
+) -> Result<Option<RdfaGraph<'a>>, RdfaError> {
+    let mut inlist_triples = vec![];
+    let mut initial_context = initial_context;
+    initial_context.base = html_base(input, &initial_context);
+    let base = document_base(input, &initial_context)?;
+    let (well_known_prefix, microdata) =
+        (initial_context.well_known_prefix, initial_context.microdata);
+    let mut processor_graph = ProcessorGraph::new(initial_context.processor_graph);
+    let resolver = initial_context
+        .vocabularies
+        .map(|v| v.0.as_ref())
+        .filter(|_| expansion::may_use_vocabulary(input));
+    let mut stmts = ExpansionSink::new(
+        sink,
+        resolver,
+        expansion::may_have_patterns(input, &initial_context),
+    );
+    stmts.base(base.clone());
+    traverse_element(
+        input,
+        None,
+        initial_context,
+        &mut stmts,
+        &mut inlist_triples,
+        &mut processor_graph,
+        bnode_ids,
+    )?;
+
+    // fixes examples/other/example0002.html
+    // when base ends with "/", inlist_triples is not append
+    // todo find a better fix
+    for stmt in inlist_triples {
+        stmts.statement(stmt.to_statement());
     }
 
-    pub fn parse_str(
-        html: &'a str,
-        base: &'a str,
-        well_known_prefix: Option<&'a str>,
-    ) -> Result<String, Box<dyn Error>> {
-        let document = scraper::Html::parse_document(html);
-        let empty_ref_node_substitue = get_uuid();
-        let root = document.root_element();
-
-        let root_ctx = Context {
-            base,
-            empty_ref_node_substitute: &empty_ref_node_substitue,
-            well_known_prefix: well_known_prefix.filter(|f| !f.is_empty()),
-            ..Default::default()
-        };
-        RdfaGraph::parse(&root, root_ctx).map(|g| g.to_string())
+    stmts.finish();
+
+    // after the expansion: rdfa:copy and vocabulary entailment only apply to RDFa
+    if microdata {
+        microdata::parse_into(input, &base, sink, bnode_ids);
     }
+
+    Ok(processor_graph.into_graph(base, well_known_prefix))
 }
-fn traverse_element<'a, 'b>(
-    element_ref: &'b ElementRef<'a>,
+
+fn traverse_element<'a, 'b, E: Element<'a>>(
+    element: &'b E,
     parent: Option<&'b Context<'a>>,
     mut ctx: Context<'a>,
-    stmts: &'b mut Vec<Statement<'a>>,
-    in_list_stmts: &mut Vec<Statement<'a>>,
-) -> Result<Option<Node<'a>>, Box<dyn Error>> {
-    let mut elt = RdfaElement::new(element_ref)?;
-
-    ctx.vocab = elt.vocab.or_else(|| parent.as_ref().and_then(|p| p.vocab));
+    stmts: &'b mut dyn TripleSink<'a>,
+    in_list_stmts: &mut Vec<NodeStatement<'a>>,
+    processor_graph: &mut ProcessorGraph<'a>,
+    bnode_ids: &mut BlankNodes,
+) -> Result<Option<Node<'a>>, RdfaError> {
+    let mut elt = RdfaElement::new(element, ctx.host_language, ctx.version)?;
+
+    if let Some(base) = elt.base {
+        ctx.base = resolve_against_base(&ctx.base, base);
+    }
 
-    ctx.base = elt.base.unwrap_or(ctx.base);
+    let base = Node::Iri(ctx.base.clone());
 
-    let base = resolve_uri(ctx.base, &ctx, true)?;
+    if let Some(vocab) = elt.vocab {
+        // an empty @vocab removes the vocab
+        ctx.vocab = Some(vocab)
+            .filter(|v| !v.is_empty())
+            .map(|v| resolve_against_base(&ctx.base, v));
+    } else if let Some(parent) = parent {
+        ctx.vocab = parent.vocab.clone();
+    }
 
-    if let Some(vocab) = ctx.vocab.filter(|v| !v.is_empty()) {
-        stmts.push(Statement {
-            subject: base.clone(),
-            predicate: NODE_RDFA_USES_VOCABULARY.clone(),
-            object: resolve_uri(vocab, &ctx, false)?,
-        })
+    if let Some(vocab) = ctx.vocab.as_ref().filter(|v| !v.is_empty()) {
+        // only an explicit @vocab generates a triple, not an inherited one
+        // nor the default vocab of the options, which the document doesn't mention
+        if elt.vocab.is_some() {
+            stmts.triple(
+                base.to_term(),
+                NODE_RDFA_USES_VOCABULARY.to_term(),
+                Node::Iri(vocab.clone()).to_term(),
+            )
+        }
     } else {
         ctx.vocab = None;
     }
-    ctx.prefixes = elt
+    // mappings are inherited and extended (RDFa Core §7.5 step 3),
+    // so that the prefixes of the options stay in scope below a @prefix
+    if let Some(parent) = parent {
+        ctx.prefixes = parent.prefixes.clone();
+    }
+    for (prefix, iri) in element
+        .namespaces()
+        .into_iter()
+        .filter_map(|(prefix, iri)| Some((prefix_name(prefix)?, iri)))
+    {
+        stmts.prefix(prefix.clone(), Cow::Borrowed(iri));
+        ctx.prefixes.insert(prefix, iri);
+    }
+    let declared_prefixes = elt
         .prefix
-        .map(parse_prefixes)
-        .or_else(|| parent.map(|p| p.prefixes.clone()))
-        .unwrap_or(ctx.prefixes);
+        .map(|p| parse_prefixes(p, &ctx, &elt, processor_graph, bnode_ids));
+    for (prefix, iri) in declared_prefixes.into_iter().flatten() {
+        stmts.prefix(prefix.clone(), Cow::Borrowed(iri));
+        ctx.prefixes.insert(prefix, iri);
+    }
 
     let is_empty_curie = |s: &str| {
         let mut s = s.trim();
@@ -134,67 +233,91 @@ fn traverse_element<'a, 'b>(
         s.is_empty()
     };
 
-    let resource = elt
-        .resource
-        .filter(|r| !is_empty_curie(r))
-        .map(|c| if c.is_empty() { ctx.base } else { c });
+    let resource = elt.resource.filter(|r| !is_empty_curie(r));
 
     ctx.lang = elt
         .lang
         .or_else(|| parent.and_then(|p| p.lang))
         .or(ctx.lang);
 
-    let mut about = elt.about.and_then(|a| resolve_uri(a, &ctx, true).ok());
+    // an empty SafeCURIE is kept to tell it apart from a missing @about, see earl_html5/0297
+    let mut about = elt.about.and_then(|a| {
+        if is_empty_curie(a) {
+            Some(base.clone())
+        } else {
+            resolve_or_report(a, &ctx, true, "about", &elt, processor_graph, bnode_ids)
+        }
+    });
 
-    let mut rels = elt.rel.map(|r| parse_property_or_type_of(r, &ctx, true));
-    let mut revs = elt.rev.map(|r| parse_property_or_type_of(r, &ctx, true));
+    let mut rels = elt
+        .rel
+        .map(|r| parse_property_or_type_of(r, &ctx, true, "rel", &elt, processor_graph, bnode_ids));
+    let mut revs = elt
+        .rev
+        .map(|r| parse_property_or_type_of(r, &ctx, true, "rev", &elt, processor_graph, bnode_ids));
 
     let mut parent_in_rel = parent.and_then(|c| c.in_rel.clone());
     let mut parent_in_rev = parent.and_then(|c| c.in_rev.clone());
     let mut parent_in_list = parent.and_then(|c| c.in_list.clone());
 
-    let mut src_or_href = elt
-        .src_or_href()
-        .and_then(|v| resolve_uri(v, &ctx, true).ok());
+    let mut src_or_href = elt.src_or_href().and_then(|v| {
+        let attribute = if elt.src.is_some() { "src" } else { "href" };
+        resolve_or_report(v, &ctx, true, attribute, &elt, processor_graph, bnode_ids)
+    });
 
-    let mut type_ofs = elt.type_of.and_then(|t| {
+    let mut type_ofs = elt.type_of.map(|t| {
         if t.trim().is_empty() {
             // use vocab
-            resolve_uri(ctx.vocab.unwrap_or(ctx.base), &ctx, true)
-                .ok()
-                .map(|v| vec![v])
+            vec![Node::Iri(ctx.vocab.clone().unwrap_or(ctx.base.clone()))]
         } else {
-            Some(parse_property_or_type_of(t, &ctx, true))
+            parse_property_or_type_of(t, &ctx, true, "typeof", &elt, processor_graph, bnode_ids)
         }
     });
 
-    let datatype = elt
-        .datatype
-        .and_then(|dt| match resolve_uri(dt, &ctx, false) {
-            Ok(d) => Some(Box::new(d)),
-            Err(e) => {
-                debug!("could not parse {dt}. error {e}");
-                None
-            }
-        });
+    let datatype = elt.datatype.and_then(|dt| {
+        resolve_or_report(
+            dt,
+            &ctx,
+            false,
+            "datatype",
+            &elt,
+            processor_graph,
+            bnode_ids,
+        )
+        .map(Box::new)
+    });
+
+    let mut predicates = elt.property.map(|p| {
+        parse_property_or_type_of(p, &ctx, false, "property", &elt, processor_graph, bnode_ids)
+    });
 
-    let mut predicates = elt
-        .property
-        .map(|p| parse_property_or_type_of(p, &ctx, false));
+    // in RDFa 1.0, @typeof types the new subject, a new blank node when there is none
+    let is_head_or_body = ctx.host_language.is_html() && (elt.name == "body" || elt.name == "head");
+    if ctx.version == RdfaVersion::Rdfa10
+        && (about.is_some() || !is_head_or_body)
+        && let Some(type_ofs) = type_ofs.take()
+    {
+        let subject = about.get_or_insert_with(|| make_bnode(bnode_ids));
+        for type_of in type_ofs {
+            stmts.triple(subject.to_term(), NODE_NS_TYPE.to_term(), type_of.to_term());
+        }
+    }
 
     // by default, current node set as the base unless it's a special node
     // check other/example0006 for special node
     let mut current_node = if !IS_SPECIAL_NODE_FN(&datatype) {
         base.clone()
     } else {
-        make_bnode()
+        make_bnode(bnode_ids)
     };
 
     // if parent is inlist
     if let Some(parent_in_list) = parent_in_list.take() {
-        let subject = get_parent_subject(&parent, &ctx)?;
+        let subject = get_parent_subject(&parent, &ctx).map_err(|e| e.in_element(elt.name))?;
         let obj = if let Some(resource) = resource
-            .and_then(|r| resolve_uri(r, &ctx, true).ok())
+            .and_then(|r| {
+                resolve_or_report(r, &ctx, true, "resource", &elt, processor_graph, bnode_ids)
+            })
             .map(|n| Node::Ref(Arc::new(n)))
             .or_else(|| src_or_href.clone())
         {
@@ -203,7 +326,7 @@ fn traverse_element<'a, 'b>(
             Node::Ref(Arc::new(extract_literal(&elt, &datatype, &ctx)?))
         };
         for rel in parent_in_list {
-            push_triples_inlist(in_list_stmts, &subject, rel, &obj);
+            push_triples_inlist(in_list_stmts, &subject, rel, &obj, bnode_ids);
         }
         current_node = subject;
     }
@@ -211,7 +334,7 @@ fn traverse_element<'a, 'b>(
     else if elt.is_inlist() {
         let mut in_rel = false;
 
-        let subject = get_parent_subject(&parent, &ctx)?;
+        let subject = get_parent_subject(&parent, &ctx).map_err(|e| e.in_element(elt.name))?;
 
         if rels.is_some()
             && src_or_href.is_none()
@@ -220,15 +343,15 @@ fn traverse_element<'a, 'b>(
             && about.is_none()
         // empty list
         {
-            if element_ref.children().count() != 0 {
+            if !element.children().is_empty() || !element.texts().is_empty() {
                 // example0013 && example0014
                 if type_ofs.is_some() {
                     let Some(rels) = rels.take() else {
                         unreachable!()
                     };
-                    current_node = make_bnode();
+                    current_node = make_bnode(bnode_ids);
                     handle_children(NodeContext {
-                        element_ref,
+                        element,
                         ctx: ctx.clone(),
                         stmts,
                         current_node: current_node.clone(),
@@ -239,6 +362,8 @@ fn traverse_element<'a, 'b>(
                         parent_in_rel: parent_in_rel.take(),
                         parent_in_rev: parent_in_rev.take(),
                         parent: &parent,
+                        processor_graph,
+                        bnode_ids,
                     })?;
                     for rel in rels {
                         let mut existing_rel_in_list = None;
@@ -256,9 +381,16 @@ fn traverse_element<'a, 'b>(
                                 &subject,
                                 rel,
                                 &existing_rel_in_list,
+                                bnode_ids,
                             );
                         } else {
-                            push_triples_inlist(in_list_stmts, &subject, rel, &current_node);
+                            push_triples_inlist(
+                                in_list_stmts,
+                                &subject,
+                                rel,
+                                &current_node,
+                                bnode_ids,
+                            );
                         }
                     }
                     return Ok(Some(subject));
@@ -266,13 +398,22 @@ fn traverse_element<'a, 'b>(
                     ctx.in_list = rels.take();
                 }
             } else {
-                push_triples(in_list_stmts, &subject, &rels.take(), &NODE_RDF_NIL);
+                // empty list
+                for rel in rels.take().into_iter().flatten() {
+                    in_list_stmts.push(NodeStatement {
+                        subject: subject.clone(),
+                        predicate: rel,
+                        object: NODE_RDF_NIL.clone(),
+                    });
+                }
             }
         } else if let Some(rels) = rels.take().filter(|r| !r.is_empty()) {
             in_rel = true;
 
             let obj = if let Some(resource) = resource
-                .and_then(|r| resolve_uri(r, &ctx, true).ok())
+                .and_then(|r| {
+                    resolve_or_report(r, &ctx, true, "resource", &elt, processor_graph, bnode_ids)
+                })
                 .map(|n| Node::Ref(Arc::new(n)))
                 .or_else(|| src_or_href.clone())
             {
@@ -281,17 +422,20 @@ fn traverse_element<'a, 'b>(
                 Node::Ref(Arc::new(extract_literal(&elt, &datatype, &ctx)?))
             };
             for rel in rels {
-                push_triples_inlist(in_list_stmts, &subject, rel, &obj);
+                push_triples_inlist(in_list_stmts, &subject, rel, &obj, bnode_ids);
             }
         }
         let obj = if let (Some(resource), false) = (resource, in_rel) {
-            Node::Ref(Arc::new(resolve_uri(resource, &ctx, true)?))
+            Node::Ref(Arc::new(
+                resolve_node(resource, &ctx, true, bnode_ids)
+                    .map_err(|e| e.in_attribute(elt.name, "resource"))?,
+            ))
         } else {
             Node::Ref(Arc::new(extract_literal(&elt, &datatype, &ctx)?))
         };
         if let Some(predicates) = predicates.take() {
             for predicate in predicates {
-                push_triples_inlist(in_list_stmts, &subject, predicate, &obj);
+                push_triples_inlist(in_list_stmts, &subject, predicate, &obj, bnode_ids);
             }
         }
 
@@ -299,7 +443,10 @@ fn traverse_element<'a, 'b>(
     }
     // if there is a resource attr
     else if let Some(resource) = resource {
-        let resource = Node::Ref(Arc::new(resolve_uri(resource, &ctx, true)?));
+        let resource = Node::Ref(Arc::new(
+            resolve_node(resource, &ctx, true, bnode_ids)
+                .map_err(|e| e.in_attribute(elt.name, "resource"))?,
+        ));
 
         if !elt.has_content_or_datatype() {
             let object = about
@@ -311,7 +458,8 @@ fn traverse_element<'a, 'b>(
             let subject = about
                 .take()
                 .map(|a| Ok(Node::Ref(Arc::new(a))))
-                .unwrap_or_else(|| get_parent_subject(&parent, &ctx))?;
+                .unwrap_or_else(|| get_parent_subject(&parent, &ctx))
+                .map_err(|e| e.in_element(elt.name))?;
 
             push_triples(stmts, &subject, &predicates, &current_node);
 
@@ -362,12 +510,14 @@ fn traverse_element<'a, 'b>(
             push_triples(stmts, &src_or_href, &revs, &current_node);
         }
         if is_empty {
-            current_node = make_bnode();
+            current_node = make_bnode(bnode_ids);
         }
     }
     // now the interesting bits
-    else if src_or_href.is_some() && elt.has_content_or_datatype() {
-        current_node = src_or_href.take().ok_or("no src")?;
+    else if elt.has_content_or_datatype()
+        && let Some(src_or_href) = src_or_href.take()
+    {
+        current_node = src_or_href;
 
         push_triples(
             stmts,
@@ -376,40 +526,21 @@ fn traverse_element<'a, 'b>(
             &extract_literal(&elt, &datatype, &ctx)?,
         );
     }
-    // test 0303
-    else if src_or_href.is_some() && (rels.is_some() || revs.is_some()) {
-        let src_or_href = src_or_href.take().ok_or("no src")?;
+    // test 0303, terms of @rel are already removed when there is a @property
+    else if (rels.is_some() || revs.is_some())
+        && let Some(src_or_href) = src_or_href.take()
+    {
         current_node = get_parent_subject(&parent, &ctx)
             .ok()
-            .unwrap_or_else(make_bnode);
-
-        let mut has_term = false;
-        let mut emit_triple = false;
-        if elt.has_property() {
-            rels = rels.take().map(|rs| {
-                rs.into_iter()
-                    .filter(|r| {
-                        let m = matches!(r, Node::Ref(r) if matches!(r.as_ref(), Node::TermIri(_)));
-                        if m {
-                            has_term = true;
-                        } else {
-                            emit_triple = true;
-                        }
-                        !m
-                    })
-                    .collect()
-            });
-        }
+            .unwrap_or_else(|| make_bnode(bnode_ids));
 
         push_triples(stmts, &current_node, &rels, &src_or_href);
         push_triples(stmts, &src_or_href, &revs, &current_node);
 
-        if has_term {
-            if emit_triple {
-                elt.src.take();
-                elt.href.take();
-            }
-
+        // @href is the object of @rel, @property gets the content
+        if elt.has_property() {
+            elt.src.take();
+            elt.href.take();
             push_triples(
                 stmts,
                 &current_node,
@@ -431,23 +562,13 @@ fn traverse_element<'a, 'b>(
             rels.take();
         }
         // example0012
-        if revs.is_some() {
-            if predicates.is_some() {
-                elt.src.take();
-                elt.href.take();
-                push_triples(
-                    stmts,
-                    &current_node,
-                    &predicates,
-                    &extract_literal(&elt, &datatype, &ctx)?,
-                );
-            }
-            if let Some(type_ofs) = type_ofs.take() {
-                let pred = Some(vec![NODE_NS_TYPE.clone()]);
+        if revs.is_some()
+            && let Some(type_ofs) = type_ofs.take()
+        {
+            let pred = Some(vec![NODE_NS_TYPE.clone()]);
 
-                for to in type_ofs {
-                    push_triples(stmts, &src_or_href, &pred, &to);
-                }
+            for to in type_ofs {
+                push_triples(stmts, &src_or_href, &pred, &to);
             }
         }
     }
@@ -457,32 +578,27 @@ fn traverse_element<'a, 'b>(
             && !elt.has_content_or_datatype()
             && (parent_in_rel.is_some() || parent_in_rev.is_some())
         {
-            current_node = make_bnode();
-            let node = src_or_href.take().unwrap_or_else(make_bnode);
+            current_node = make_bnode(bnode_ids);
+            let node = src_or_href.take().unwrap_or_else(|| make_bnode(bnode_ids));
             for to in type_ofs.take().iter().flatten() {
                 push_triples(stmts, &node, &Some(vec![NODE_NS_TYPE.clone()]), to);
             }
             push_triples(stmts, &current_node, &predicates, &node);
         } else if rels.is_some() {
-            current_node = make_bnode();
+            current_node = make_bnode(bnode_ids);
 
             for to in type_ofs.take().into_iter().flatten() {
-                stmts.push(Statement {
-                    subject: current_node.clone(),
-                    predicate: NODE_NS_TYPE.clone(),
-                    object: to,
-                })
+                stmts.triple(current_node.to_term(), NODE_NS_TYPE.to_term(), to.to_term())
             }
             push_triples(stmts, &base, &rels.take(), &current_node);
         } else if !IS_SPECIAL_NODE_FN(&datatype) {
             // property shouldn't be in the list
             // fixme
-            let child_with_rdfa_tag = element_ref
-                .select(&Selector::parse(
-                    "[href], [src], [resource], [property], [about]",
-                )?)
-                .filter(|e| {
-                    RdfaElement::new(e)
+            let child_with_rdfa_tag = !element.descendants().into_iter().any(|e| {
+                ["href", "src", "resource", "property", "about"]
+                    .iter()
+                    .any(|a| e.attr(a).is_some())
+                    && RdfaElement::new(&e, ctx.host_language, ctx.version)
                         .ok()
                         .and_then(|e2| e2.datatype)
                         .and_then(|dt| match resolve_uri(dt, &ctx, false).ok().map(Box::new) {
@@ -490,25 +606,19 @@ fn traverse_element<'a, 'b>(
                             _ => None,
                         })
                         .is_none()
-                })
-                .count()
-                == 0;
+            });
             current_node = if let Some(src_or_href) = src_or_href.take() {
                 src_or_href
             // not sure about this rule
-            } else if elt.name == "body"
-                || elt.name == "head"
-                || child_with_rdfa_tag
-                || parent.is_none()
-            {
+            } else if is_head_or_body || child_with_rdfa_tag || parent.is_none() {
                 base.clone()
             } else {
-                make_bnode()
+                make_bnode(bnode_ids)
             };
 
             let subject = get_parent_subject(&parent, &ctx)
                 .ok()
-                .unwrap_or_else(make_bnode);
+                .unwrap_or_else(|| make_bnode(bnode_ids));
 
             push_triples(stmts, &subject, &predicates, &current_node);
         } else {
@@ -527,7 +637,8 @@ fn traverse_element<'a, 'b>(
             .take()
             .filter(|_| parent_in_rel.is_some() || parent_in_rev.is_some())
             .map(Ok)
-            .unwrap_or_else(|| get_parent_subject(&parent, &ctx))?;
+            .unwrap_or_else(|| get_parent_subject(&parent, &ctx))
+            .map_err(|e| e.in_element(elt.name))?;
 
         push_triples(
             stmts,
@@ -538,7 +649,7 @@ fn traverse_element<'a, 'b>(
     }
 
     handle_children(NodeContext {
-        element_ref,
+        element,
         ctx,
         stmts,
         current_node,
@@ -549,11 +660,13 @@ fn traverse_element<'a, 'b>(
         parent_in_rel,
         parent_in_rev,
         parent: &parent,
+        processor_graph,
+        bnode_ids,
     })
 }
-fn handle_children<'a>(
+fn handle_children<'a, E: Element<'a>>(
     NodeContext {
-        element_ref,
+        element,
         mut ctx,
         stmts,
         current_node,
@@ -564,108 +677,135 @@ fn handle_children<'a>(
         mut parent_in_rel,
         mut parent_in_rev,
         parent,
-    }: NodeContext<'a, '_>,
-) -> Result<Option<Node<'a>>, Box<dyn Error>> {
+        processor_graph,
+        bnode_ids,
+    }: NodeContext<'a, '_, E>,
+) -> Result<Option<Node<'a>>, RdfaError> {
     if let Some(type_ofs) = type_ofs {
         for type_of in type_ofs {
-            stmts.push(Statement {
-                subject: current_node.clone(),
-                predicate: NODE_NS_TYPE.clone(),
-                object: type_of,
-            })
+            stmts.triple(
+                current_node.to_term(),
+                NODE_NS_TYPE.to_term(),
+                type_of.to_term(),
+            )
         }
     }
 
     if parent_in_rel.is_some() || parent_in_rev.is_some() {
-        let parent = get_parent_subject(parent, &ctx)
-            .ok()
-            .ok_or("in_rel: no parent node")?;
+        let parent = get_parent_subject(parent, &ctx).map_err(|e| e.in_element(element.name()))?;
         push_triples(stmts, &parent, &parent_in_rel.take(), &current_node);
         push_triples(stmts, &current_node, &parent_in_rev.take(), &parent);
     }
     ctx.current_node = Some(current_node.clone());
     ctx.in_rel = rels.clone();
     ctx.in_rev = revs.clone();
-    for child in get_children(element_ref)? {
-        if let Some(c) = ElementRef::wrap(child) {
+    for c in get_children(element) {
+        // Triples are also 'completed' if any one of @property, @rel or @rev are present.
+        let triples_completed = (ctx.in_rel.is_some() || ctx.in_rev.is_some())
+            && (c.attr("property").is_some() || c.attr("rel").is_some() || c.attr("rev").is_some())
+            && (c.attr("about").is_none() && c.attr("typeof").is_none());
+
+        if triples_completed {
             // Triples are also 'completed' if any one of @property, @rel or @rev are present.
-            let triples_completed = (ctx.in_rel.is_some() || ctx.in_rev.is_some())
-                && (c.attr("property").is_some()
-                    || c.attr("rel").is_some()
-                    || c.attr("rev").is_some())
-                && (c.attr("about").is_none() && c.attr("typeof").is_none());
-
-            if triples_completed {
-                // Triples are also 'completed' if any one of @property, @rel or @rev are present.
-                let b_node = make_bnode();
-                push_triples(stmts, &current_node, &ctx.in_rel.take(), &b_node);
-                push_triples(stmts, &b_node, &ctx.in_rev.take(), &current_node);
-
-                ctx.current_node = Some(b_node);
-            }
-            // However, unlike the situation when @about or @typeof are present, all predicates are attached to one bnode
-            if c.attr("about").is_some() || c.attr("typeof").is_some() {
-                ctx.in_rel = rels.clone();
-                ctx.in_rev = revs.clone();
-                ctx.current_node = Some(current_node.clone());
-            }
-            let child_ctx = Context {
-                base: ctx.base,
-                lang: ctx.lang,
-                empty_ref_node_substitute: ctx.empty_ref_node_substitute,
-                ..Default::default()
-            };
+            let b_node = make_bnode(bnode_ids);
+            push_triples(stmts, &current_node, &ctx.in_rel.take(), &b_node);
+            push_triples(stmts, &b_node, &ctx.in_rev.take(), &current_node);
 
-            let node = traverse_element(&c, Some(&ctx), child_ctx, stmts, in_list_stmts)?;
-            if node != ctx.current_node {
-                stmts.append(in_list_stmts);
+            ctx.current_node = Some(b_node);
+        }
+        // However, unlike the situation when @about or @typeof are present, all predicates are attached to one bnode
+        if c.attr("about").is_some() || c.attr("typeof").is_some() {
+            ctx.in_rel = rels.clone();
+            ctx.in_rev = revs.clone();
+            ctx.current_node = Some(current_node.clone());
+        }
+        let child_ctx = Context {
+            base: ctx.base.clone(),
+            lang: ctx.lang,
+            host_language: ctx.host_language,
+            version: ctx.version,
+            initial_context: ctx.initial_context,
+            ..Default::default()
+        };
+
+        let node = traverse_element(
+            &c,
+            Some(&ctx),
+            child_ctx,
+            stmts,
+            in_list_stmts,
+            processor_graph,
+            bnode_ids,
+        )?;
+        if node != ctx.current_node {
+            for stmt in in_list_stmts.drain(..) {
+                stmts.statement(stmt.to_statement());
             }
         }
     }
     Ok(ctx.current_node.clone())
 }
-fn extract_literal<'a>(
-    rdfa_el: &RdfaElement<'a, '_>,
+fn extract_literal<'a, E: Element<'a>>(
+    rdfa_el: &RdfaElement<'a, '_, E>,
     datatype: &Option<Box<Node<'a>>>,
     ctx: &Context<'a>,
-) -> Result<Node<'a>, &'static str> {
+) -> Result<Node<'a>, RdfaError> {
     let plain_datatype = datatype
         .as_ref()
         .filter(|dt| dt.as_ref() == &*NODE_RDF_PLAIN_LITERAL)
         .is_some();
 
-    let lang = ctx.lang.filter(|s| datatype.is_none() && !s.is_empty());
-    if let Some(value) = rdfa_el.src_or_href().filter(|_| {
-        !rdfa_el.has_about() && !rdfa_el.has_property() || !rdfa_el.has_content_or_datatype()
+    let lang = ctx
+        .lang
+        .filter(|s| datatype.is_none() && !s.is_empty())
+        .map(Cow::Borrowed);
+    // a SafeCURIE in @src or @href is reported and ignored when resolving them
+    if let Some(value) = rdfa_el.src_or_href().filter(|v| {
+        !is_safe_curie(v.trim())
+            && (!rdfa_el.has_about() && !rdfa_el.has_property()
+                || !rdfa_el.has_content_or_datatype())
     }) {
-        resolve_uri(value, ctx, true)
-    } else if let Some(content) = rdfa_el.content {
-        Ok(Node::Literal(Literal {
-            datatype: datatype.clone(),
-            value: Cow::Borrowed(content),
-            lang,
-        }))
+        resolve_iri(value, ctx).map_err(|e| {
+            let attribute = if rdfa_el.src.is_some() { "src" } else { "href" };
+            e.in_attribute(rdfa_el.name, attribute)
+        })
+    } else if let Some(content) = rdfa_el.content.or_else(|| rdfa_el.get_value()) {
+        Ok(literal(Cow::Borrowed(content), datatype.as_deref(), lang))
     } else if !plain_datatype && IS_SPECIAL_NODE_FN(datatype) {
-        Ok(Node::Literal(Literal {
-            value: Cow::Owned(rdfa_el.inner_html()),
-            datatype: datatype.clone(),
-            lang: None,
-        }))
+        let value = if datatype.as_deref() == Some(&NODE_RDF_XML_LITERAL) {
+            rdfa_el.xml_literal()
+        } else {
+            rdfa_el.inner_html()
+        };
+        Ok(literal(Cow::Owned(value), datatype.as_deref(), None))
+    } else if rdfa_el.version == RdfaVersion::Rdfa10
+        && datatype.is_none()
+        && !rdfa_el.element.children().is_empty()
+    {
+        // mixed content
+        Ok(literal(
+            Cow::Owned(rdfa_el.xml_literal()),
+            Some(&NODE_RDF_XML_LITERAL),
+            None,
+        ))
     } else if let Some(content) = rdfa_el.get_time() {
-        Ok(Node::Literal(Literal {
-            datatype: datatype
-                .clone()
-                .or_else(|| DataTypeFromPattern::date_time_from_pattern(content).map(Box::new)),
-            value: Cow::Borrowed(content),
-            lang: None,
-        }))
+        let datatype = datatype
+            .as_deref()
+            .cloned()
+            .or_else(|| DataTypeFromPattern::date_time_from_pattern(&content));
+        // a value that is not a date, a time nor a duration is a plain literal
+        Ok(literal(content, datatype.as_ref(), lang))
     } else {
         let datatype = if plain_datatype {
             None
         } else {
-            datatype.clone()
+            datatype.as_deref()
+        };
+        let lang = if plain_datatype {
+            ctx.lang.map(Cow::Borrowed)
+        } else {
+            lang
         };
-        let lang = if plain_datatype { ctx.lang } else { lang };
         let texts = rdfa_el.texts();
         let text = if texts.is_empty() {
             Cow::Borrowed("")
@@ -677,214 +817,335 @@ fn extract_literal<'a>(
                 .join("");
             Cow::Owned(text)
         };
-        Ok(Node::Literal(Literal {
-            datatype,
-            value: text,
-            lang,
-        }))
+        Ok(literal(text, datatype, lang))
     }
 }
+
+/// A datatype wins over the language.
+fn literal<'a>(
+    value: Cow<'a, str>,
+    datatype: Option<&Node<'a>>,
+    lang: Option<Cow<'a, str>>,
+) -> Node<'a> {
+    Node::Literal(match (datatype.and_then(Node::to_iri), lang) {
+        (Some(datatype), _) => Literal::typed(value, datatype),
+        (None, Some(lang)) => Literal::lang_tagged(value, lang),
+        (None, None) => Literal::new(value),
+    })
+}
 fn get_parent_subject<'a>(
     parent: &Option<&Context<'a>>,
     ctx: &Context<'a>,
-) -> Result<Node<'a>, Box<dyn Error>> {
+) -> Result<Node<'a>, RdfaError> {
     parent
         .and_then(|p| p.current_node.clone())
         .or_else(|| {
             if parent.is_none() {
-                resolve_uri(ctx.base, ctx, true).ok()
+                Some(Node::Iri(ctx.base.clone()))
             } else {
                 None
             }
         })
-        .ok_or("no parent".into())
+        .ok_or_else(|| RdfaError::NoParentSubject {
+            location: ErrorLocation::new(&ctx.base),
+        })
 }
 
 fn resolve_uri<'a>(
     uri: &'a str,
     ctx: &Context<'a>,
     is_resource: bool,
-) -> Result<Node<'a>, &'static str> {
+) -> Result<Node<'a>, RdfaError> {
     let uri = uri.trim();
 
-    // special case, see bug#
-    if let Ok(ref origin) = Url::parse(ctx.base).map(|u| u.origin())
-        && let Origin::Tuple(_, host, _) = origin
-    {
-        let host = &host.to_string();
-
-        if uri.starts_with(host) {
-            return Ok(Node::TermIri(Cow::Owned(
-                uri.replace(host, &origin.unicode_serialization()),
-            )));
+    if uri.starts_with('[') {
+        return match parse_safe_curie(uri) {
+            Ok(Some(curie)) => resolve_curie(curie, ctx).ok_or(RdfaError::UnresolvedCurie {
+                value: uri.to_string(),
+                location: ErrorLocation::new(&ctx.base),
+            }),
+            _ => Err(RdfaError::InvalidSafeCurie {
+                value: uri.to_string(),
+                location: ErrorLocation::new(&ctx.base),
+            }),
+        };
+    }
+    // a CURIE with an unknown prefix is an IRI, e.g mailto:me@example.com
+    if let Some(curie) = Curie::parse(uri) {
+        if let Some(node) = resolve_curie(curie, ctx) {
+            return Ok(node);
         }
-    };
-
-    let iri = Url::parse(uri);
-    let trailing_white_space = if ctx.base.ends_with('/')
-        || ctx.base.ends_with('#')
-        || uri.starts_with('/')
-        || uri.starts_with('#')
-    {
-        ""
-    } else {
-        "/"
-    };
-    match iri {
-        Ok(iri) if !iri.cannot_be_a_base() || iri.is_special() => {
-            // special case pct encoded, see other/example0004
-            if uri.contains(|c: char| c.is_whitespace() || c.is_control()) {
-                let mut new_uri = String::with_capacity(uri.len() * 125 / 100);
-                for c in uri.chars() {
-                    match c {
-                        '\n' => new_uri.push_str("%0A"),
-                        '\0' => new_uri.push_str("%00"),
-                        '\t' => new_uri.push_str("%09"),
-                        '\r' => new_uri.push_str("%0D"),
-                        ' ' => new_uri.push_str("%20"),
-                        c => new_uri.push(c),
-                    }
-                }
-                Ok(Node::Iri(Cow::Owned(new_uri)))
-            } else {
-                Ok(Node::Iri(Cow::Borrowed(uri)))
-            }
+        // RDFa 1.0 ignores it outside @about and @resource
+        if ctx.version == RdfaVersion::Rdfa10 && !is_resource {
+            return Err(RdfaError::UnresolvedCurie {
+                value: uri.to_string(),
+                location: ErrorLocation::new(&ctx.base),
+            });
         }
+    }
+    resolve_iri_or_term(uri, ctx, is_resource)
+}
 
-        // Curie
-        Ok(iri) => {
-            if uri.starts_with("mail:") || uri.starts_with("tel:") {
-                Ok(Node::Iri(Cow::Borrowed(uri)))
-            } else if let Some((prefix, value)) = ctx
-                .prefixes
-                .iter()
-                .find(|(k, _)| k.eq_ignore_ascii_case(iri.scheme()))
-            {
-                let iri = format!(
-                    "{value}{}",
-                    &uri.replacen(':', "", 1).trim()[prefix.len()..]
-                );
-                Ok(Node::Iri(Cow::Owned(iri)))
-            } else if let Some((prefix, value)) = COMMON_PREFIXES
-                .iter()
-                .find(|(k, _)| k.eq_ignore_ascii_case(iri.scheme()))
-            {
-                let iri = format!(
-                    "{value}{}",
-                    &uri.replacen(':', "", 1).trim()[prefix.len()..]
-                );
-                Ok(Node::Iri(Cow::Owned(iri)))
-            } else {
-                Ok(Node::Iri(Cow::Owned(uri.to_string())))
-            }
-        }
+/// Resolve a value, a blank node label of the document gets its identifier for this parse.
+fn resolve_node<'a>(
+    uri: &'a str,
+    ctx: &Context<'a>,
+    is_resource: bool,
+    bnode_ids: &mut BlankNodes,
+) -> Result<Node<'a>, RdfaError> {
+    resolve_uri(uri, ctx, is_resource).map(|node| match node {
+        Node::RefBlank(label) => Node::Blank(bnode_ids.label(&label)),
+        node => node,
+    })
+}
+
+/// Resolve an attribute that only takes IRIs (@href, @src): no CURIE nor SafeCURIE.
+fn resolve_iri<'a>(uri: &'a str, ctx: &Context<'a>) -> Result<Node<'a>, RdfaError> {
+    let uri = uri.trim();
+    if is_safe_curie(uri) {
+        return Err(RdfaError::InvalidIri {
+            value: uri.to_string(),
+            reason: "a SafeCURIE is not an IRI".to_string(),
+            location: ErrorLocation::new(&ctx.base),
+        });
+    }
+    resolve_iri_or_term(uri, ctx, true)
+}
+
+fn resolve_curie<'a>(curie: Curie<'a>, ctx: &Context<'a>) -> Option<Node<'a>> {
+    let reference = curie.reference.trim();
+    if curie.is_blank_node() {
+        // `[_:]` is an empty label
+        return Some(Node::RefBlank(Cow::Borrowed(reference)));
+    }
+    let prefix = curie.normalized_prefix();
+    let iri = ctx
+        .prefixes
+        .get(&prefix)
+        .copied()
+        .or_else(|| ctx.initial_context().get_prefix(&prefix))?;
+    Some(Node::Iri(Cow::Owned([iri, reference].join(""))))
+}
+
+fn resolve_iri_or_term<'a>(
+    uri: &'a str,
+    ctx: &Context<'a>,
+    is_resource: bool,
+) -> Result<Node<'a>, RdfaError> {
+    match Url::parse(uri) {
+        // special case pct encoded, see other/example0004
+        Ok(_) => Ok(Node::Iri(iri::encode_disallowed(uri))),
         Err(url::ParseError::RelativeUrlWithoutBase) => {
-            if let Ok((prefix, reference)) = parse_safe_curie(uri) {
-                let reference = reference.trim();
-                let prefix = prefix.trim();
-                if prefix == "_" {
-                    let id = if reference.is_empty() {
-                        ctx.empty_ref_node_substitute
-                    } else {
-                        reference
-                    };
-                    return Ok(Node::RefBlank(id));
-                } else if prefix.is_empty() && !reference.is_empty() {
-                    return Ok(Node::TermIri(Cow::Owned(
-                        [COMMON_PREFIXES[""], reference].join(""),
-                    )));
-                } else if let Some(prefix) = ctx
-                    .prefixes
-                    .get(prefix)
-                    .or_else(|| COMMON_PREFIXES.get(prefix))
-                {
-                    let reference = if reference.trim().is_empty() {
-                        reference.trim()
-                    } else {
-                        reference
-                    };
-                    return Ok(Node::Iri(Cow::Owned([prefix, reference].join(""))));
-                }
-            }
             if is_resource || uri.starts_with('#') || uri.starts_with('/') {
-                let uri = if uri.starts_with("/") && ctx.base.ends_with("/") {
-                    &uri[1..]
+                // a reference starting with the host of the base is missing its `//`
+                let uri = if starts_with_host(&ctx.base, uri) {
+                    Cow::Owned(format!("//{uri}"))
                 } else {
-                    uri
+                    Cow::Borrowed(uri)
                 };
                 Ok(Node::TermIri(Cow::Owned(
-                    [ctx.base, trailing_white_space, uri].join(""),
+                    iri::encode_disallowed(&iri::resolve(&ctx.base, &uri)).into_owned(),
                 )))
-            } else if let Some(vocab) = ctx.vocab {
+            } else if let Some(vocab) = &ctx.vocab {
                 Ok(Node::TermIri(Cow::Owned([vocab, uri].join(""))))
-            } else if RESERVED_KEYWORDS
-                .iter()
-                .any(|w| uri.eq_ignore_ascii_case(w))
-            {
-                Ok(Node::TermIri(Cow::Borrowed(
-                    COMMON_PREFIXES[uri.to_lowercase().as_str()],
-                )))
+            } else if let Some(iri) = ctx.initial_context().get_term(uri) {
+                Ok(Node::TermIri(Cow::Borrowed(iri)))
             } else {
                 debug!("could not determine base/vocab {:?}", ctx);
-                // Ok(Node::Iri(Cow::Borrowed(uri)))
-                Err("could not determine uri")
+                Err(RdfaError::UnresolvedTerm {
+                    value: uri.to_string(),
+                    location: ErrorLocation::new(&ctx.base),
+                })
             }
         }
-        Err(e) => {
-            eprintln!("invalid uri {uri}. error: {e}");
-            Err("could not resolve uri")
-        }
+        Err(e) => Err(RdfaError::InvalidIri {
+            value: uri.to_string(),
+            reason: e.to_string(),
+            location: ErrorLocation::new(&ctx.base),
+        }),
     }
 }
 
-fn parse_safe_curie(s: &str) -> Result<(&str, &str), &'static str> {
-    let mut s = s.trim();
-    if s.starts_with('[') {
-        if !s.ends_with(']') {
-            return Err("invalid SafeCurie");
+/// e.g `example.org/page` with the base `http://example.org/`, see tests/bug.rs
+fn starts_with_host(base: &str, reference: &str) -> bool {
+    let Ok(base) = Url::parse(base) else {
+        return false;
+    };
+    base.host_str()
+        .and_then(|host| reference.strip_prefix(host))
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#']))
+}
+
+fn parse_prefixes<'a, E: Element<'a>>(
+    s: &'a str,
+    ctx: &Context<'a>,
+    elt: &RdfaElement<'a, '_, E>,
+    processor_graph: &mut ProcessorGraph<'a>,
+    bnode_ids: &mut BlankNodes,
+) -> HashMap<Cow<'a, str>, &'a str> {
+    let mut report = |error: RdfaError| {
+        debug!("{error}");
+        processor_graph.report(
+            &error.in_attribute(elt.name, "prefix"),
+            elt.element,
+            bnode_ids,
+        );
+    };
+    let mut prefixes = HashMap::new();
+    for declaration in prefix_declarations(s, &ctx.base) {
+        match declaration {
+            Ok((s, p)) => {
+                // declared twice in the attribute, or in scope with another IRI
+                let in_scope = ctx.prefixes.get(&s).is_some_and(|iri| *iri != p);
+                if prefixes.insert(s.clone(), p).is_some() || in_scope {
+                    report(RdfaError::PrefixRedefinition {
+                        value: s.to_string(),
+                        location: ErrorLocation::new(&ctx.base),
+                    });
+                }
+            }
+            Err(e) => report(e),
         }
-        s = &s[1..s.len() - 1];
     }
-    s.split_once(':').ok_or("not a curie")
+    prefixes
 }
 
-fn parse_prefixes(s: &str) -> HashMap<&str, &str> {
-    s.split_whitespace()
-        .map(|s| s.trim())
-        .collect::<Vec<_>>()
+/// Declarations of a @prefix attribute, in document order.
+pub(crate) fn prefix_declarations<'a>(
+    s: &'a str,
+    base: &str,
+) -> Vec<Result<(Cow<'a, str>, &'a str), RdfaError>> {
+    let declarations = s.split_whitespace().collect::<Vec<_>>();
+    let remainder = match declarations.chunks_exact(2).remainder() {
+        [remainder] => Some(Err(RdfaError::InvalidPrefix {
+            value: remainder.to_string(),
+            location: ErrorLocation::new(base),
+        })),
+        _ => None,
+    };
+    let pairs = declarations
         .chunks_exact(2)
         .map(|c| (c[0], c[1]))
-        .filter_map(|(s, p)| {
-            if let Ok((s, _)) = parse_safe_curie(s) {
-                Some((s, p))
-            } else {
-                error!("fixme! couldn't parse curie for {s}, {p}");
-                None
-            }
-        })
-        .collect()
+        .map(|(s, p)| match parse_prefix_name(s) {
+            Some(s) => Ok((s, p)),
+            None => Err(RdfaError::InvalidPrefix {
+                value: format!("{s} {p}"),
+                location: ErrorLocation::new(base),
+            }),
+        });
+    remainder.into_iter().chain(pairs).collect()
+}
+
+/// The base of the options, resolved against the `<base>` element of html.
+/// Looked up once, the traversal only resolves @xml:base.
+fn html_base<'a>(input: &impl Element<'a>, initial_context: &Context<'a>) -> Cow<'a, str> {
+    if !initial_context.host_language.is_html() {
+        return initial_context.base.clone();
+    }
+    input
+        .descendants()
+        .into_iter()
+        .find(|e| e.name() == "base")
+        .and_then(|e| e.attr("href"))
+        .map(|href| resolve_against_base(&initial_context.base, without_fragment(href)))
+        .unwrap_or_else(|| initial_context.base.clone())
+}
+
+/// Base of the root element, resolved the same way as during the traversal.
+fn document_base<'a>(
+    input: &impl Element<'a>,
+    initial_context: &Context<'a>,
+) -> Result<Cow<'a, str>, RdfaError> {
+    let elt = RdfaElement::new(
+        input,
+        initial_context.host_language,
+        initial_context.version,
+    )?;
+    Ok(match elt.base {
+        Some(base) => resolve_against_base(&initial_context.base, base),
+        None => initial_context.base.clone(),
+    })
+}
+
+/// An absolute IRI as written, otherwise the reference resolved against the base.
+fn resolve_against_base<'a>(base: &str, reference: &'a str) -> Cow<'a, str> {
+    if Url::parse(reference).is_ok() {
+        Cow::Borrowed(reference)
+    } else {
+        Cow::Owned(iri::resolve(base, reference))
+    }
 }
 
-fn parse_property_or_type_of<'a>(
+/// Vocab of the options, otherwise the first @vocab of the document.
+fn document_vocab<'a>(
+    input: &impl Element<'a>,
+    initial_context: &Context<'a>,
+) -> Option<Cow<'a, str>> {
+    initial_context.vocab.clone().or_else(|| {
+        input
+            .descendants()
+            .into_iter()
+            .find_map(|element| element.attr("vocab").filter(|v| !v.trim().is_empty()))
+            .map(|vocab| resolve_against_base(&initial_context.base, vocab))
+    })
+}
+
+fn parse_property_or_type_of<'a, E: Element<'a>>(
     s: &'a str,
     ctx: &Context<'a>,
     allow_b_node: bool,
+    attribute: &str,
+    elt: &RdfaElement<'a, '_, E>,
+    processor_graph: &mut ProcessorGraph<'a>,
+    bnode_ids: &mut BlankNodes,
 ) -> Vec<Node<'a>> {
     s.split_whitespace()
-        .filter_map(|uri| resolve_uri(uri, ctx, false).ok())
+        .filter(|uri| !elt.is_ignored_term(attribute, uri))
+        .filter_map(|uri| {
+            resolve_or_report(uri, ctx, false, attribute, elt, processor_graph, bnode_ids)
+        })
         .filter(|node| allow_b_node || !matches!(node, Node::Blank(_) | Node::RefBlank(_)))
         .map(|n| Node::Ref(Arc::new(n)))
         .collect()
 }
 
+/// Resolve an attribute value. Values that cannot be resolved are ignored
+/// and described in the processor graph.
+fn resolve_or_report<'a, E: Element<'a>>(
+    value: &'a str,
+    ctx: &Context<'a>,
+    is_resource: bool,
+    attribute: &str,
+    elt: &RdfaElement<'a, '_, E>,
+    processor_graph: &mut ProcessorGraph<'a>,
+    bnode_ids: &mut BlankNodes,
+) -> Option<Node<'a>> {
+    // @src and @href only take IRIs
+    let resolved = if matches!(attribute, "src" | "href") {
+        resolve_iri(value, ctx)
+    } else {
+        resolve_node(value, ctx, is_resource, bnode_ids)
+    };
+    match resolved {
+        Ok(node) => Some(node),
+        Err(e) => {
+            let e = e.in_attribute(elt.name, attribute);
+            debug!("{e}");
+            processor_graph.report(&e, elt.element, bnode_ids);
+            None
+        }
+    }
+}
+
 fn push_triples_inlist<'a>(
-    stmts: &mut Vec<Statement<'a>>,
+    stmts: &mut Vec<NodeStatement<'a>>,
     subject: &Node<'a>,
     predicate: Node<'a>,
     obj: &Node<'a>,
+    bnode_ids: &mut BlankNodes,
 ) {
-    let b_node = make_bnode();
-    stmts.push(Statement {
+    let b_node = make_bnode(bnode_ids);
+    stmts.push(NodeStatement {
         subject: b_node.clone(),
         predicate: NODE_RDF_FIRST.clone(),
         object: obj.clone(),
@@ -896,24 +1157,24 @@ fn push_triples_inlist<'a>(
         node.object = b_node.clone();
     } else {
         // push the root of the list
-        stmts.push(Statement {
+        stmts.push(NodeStatement {
             subject: subject.clone(),
             predicate,
             object: b_node.clone(),
         });
     }
-    stmts.push(Statement {
+    stmts.push(NodeStatement {
         subject: b_node,
         predicate: NODE_RDF_REST.clone(),
         object: NODE_RDF_NIL.clone(),
     });
 }
 fn find_pos_last_node_in_inlist<'a>(
-    stmts: &Vec<Statement<'a>>,
+    stmts: &Vec<NodeStatement<'a>>,
     root_subject: &Node<'a>,
     predicate: &Node<'a>,
 ) -> Option<usize> {
-    fn find_res_nil<'a>(stmts: &Vec<Statement<'a>>, subject: &Node<'a>) -> Option<usize> {
+    fn find_res_nil<'a>(stmts: &Vec<NodeStatement<'a>>, subject: &Node<'a>) -> Option<usize> {
         let node = stmts
             .iter()
             .enumerate()
@@ -932,7 +1193,7 @@ fn find_pos_last_node_in_inlist<'a>(
     let root = stmts
         .iter()
         .find(|stmt| &stmt.subject == root_subject && &stmt.predicate == predicate);
-    if let Some(Statement { object, .. }) = root {
+    if let Some(NodeStatement { object, .. }) = root {
         find_res_nil(stmts, object)
     } else {
         None
@@ -941,97 +1202,34 @@ fn find_pos_last_node_in_inlist<'a>(
 
 // skip when there are no rdfa attributes, see e.g examples/earl_html5/example0084.html
 #[inline]
-fn get_children<'a>(
-    element_ref: &ElementRef<'a>,
-) -> Result<Vec<ego_tree::NodeRef<'a, scraper::Node>>, &'static str> {
+fn get_children<'a, E: Element<'a>>(element: &E) -> Vec<E> {
     let mut res = vec![];
-    for c in element_ref.children() {
-        if c.value()
-            .as_element()
-            .filter(|e| e.attrs().count() == 0)
-            .is_some()
-        {
-            let child_ref = ElementRef::wrap(c).ok_or("not an element ref")?;
-            res.append(&mut get_children(&child_ref)?);
+    for c in element.children() {
+        if !c.has_attributes() {
+            res.append(&mut get_children(&c));
         } else {
             res.push(c);
         }
     }
 
-    Ok(res)
+    res
 }
 
 #[inline]
-fn make_bnode<'a>() -> Node<'a> {
-    Node::Blank(get_uuid())
-}
-
-#[inline]
-fn copy_pattern(triples: Vec<Statement<'_>>) -> Result<Vec<Statement<'_>>, Box<dyn Error>> {
-    let (pattern_type, pattern): (Vec<Statement>, Vec<Statement>) = triples
-        .into_iter()
-        .partition(|stmt| stmt.object == *NODE_RDFA_PATTERN_TYPE);
-
-    let (pattern_predicate, pattern): (Vec<Statement>, Vec<Statement>) = pattern
-        .into_iter()
-        .partition(|stmt| pattern_type.iter().any(|s| s.subject == stmt.subject));
-
-    let (pattern_subject, mut triples): (Vec<Statement>, Vec<Statement>) = pattern
-        .into_iter()
-        .partition(|stmt| pattern_predicate.iter().any(|s| s.subject == stmt.object));
-
-    // remove only if pattern referenced
-    let (mut unreferenced_pattern_predicate, pattern_predicate): (Vec<Statement>, Vec<Statement>) =
-        pattern_predicate
-            .into_iter()
-            .partition(|stmt| pattern_subject.iter().all(|s| s.object != stmt.subject));
-
-    let (mut unreferenced_pattern_type, _): (Vec<Statement>, Vec<Statement>) =
-        pattern_type.into_iter().partition(|stmt| {
-            unreferenced_pattern_predicate
-                .iter()
-                .any(|s| s.subject == stmt.subject)
-        });
-    triples.append(&mut unreferenced_pattern_predicate);
-    triples.append(&mut unreferenced_pattern_type);
-
-    for Statement {
-        subject, object, ..
-    } in pattern_subject
-    {
-        for Statement {
-            predicate,
-            object: obj,
-            ..
-        } in pattern_predicate
-            .iter()
-            .filter(|stmt| object == stmt.subject)
-        {
-            triples.push(Statement {
-                subject: subject.clone(),
-                predicate: predicate.clone(),
-                object: obj.clone(),
-            })
-        }
-    }
-
-    Ok(triples)
+fn make_bnode<'a>(bnode_ids: &mut dyn BlankNodeIdGenerator) -> Node<'a> {
+    Node::Blank(bnode_ids.next_id())
 }
 
 #[inline]
 fn push_triples<'a>(
-    stmts: &mut Vec<Statement<'a>>,
+    stmts: &mut dyn TripleSink<'a>,
     subject: &Node<'a>,
     predicates: &Option<Vec<Node<'a>>>,
     object: &Node<'a>,
 ) {
     if let Some(predicate) = predicates {
         for predicate in predicate {
-            stmts.push(Statement {
-                subject: subject.clone(),
-                predicate: predicate.clone(),
-                object: object.clone(),
-            });
+            stmts.triple(subject.to_term(), predicate.to_term(), object.to_term());
         }
     }
 }
@@ -0,0 +1,430 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use url::Url;
+
+use crate::{
+    BlankNodeIdGenerator, Element, TripleSink,
+    constants::{MD_ITEM, NS_TYPE, RDF_FIRST, RDF_NIL, RDF_REST, XSD_DOUBLE, XSD_INTEGER},
+    resolve_against_base,
+    structs::DataTypeFromPattern,
+    term::{BlankNode, Iri, Literal, Term},
+};
+
+/// Vocabulary specific rules of the microdata registry.
+struct VocabularyRules {
+    /// `multipleValues: list`, values of a property are an rdf:List instead of separate triples.
+    list: bool,
+    /// `subPropertyOf`, a property also emitted with its super property.
+    sub_properties: &'static [(&'static str, &'static str)],
+}
+
+const ADDITIONAL_TYPE: &[(&str, &str)] = &[("additionalType", NS_TYPE)];
+
+/// https://www.w3.org/ns/md, matched against the type of an item.
+static REGISTRY: [(&str, VocabularyRules); 5] = [
+    (
+        "http://schema.org/",
+        VocabularyRules {
+            list: false,
+            sub_properties: ADDITIONAL_TYPE,
+        },
+    ),
+    (
+        "https://schema.org/",
+        VocabularyRules {
+            list: false,
+            sub_properties: ADDITIONAL_TYPE,
+        },
+    ),
+    (
+        "http://microformats.org/profile/hcard",
+        VocabularyRules {
+            list: false,
+            sub_properties: &[],
+        },
+    ),
+    (
+        "http://microformats.org/profile/hcalendar#",
+        VocabularyRules {
+            list: false,
+            sub_properties: ADDITIONAL_TYPE,
+        },
+    ),
+    (
+        "http://n.whatwg.org/work",
+        VocabularyRules {
+            list: true,
+            sub_properties: &[],
+        },
+    ),
+];
+
+/// Document in tree order, elements are referred to by their position.
+struct Tree<'a, E> {
+    elements: Vec<E>,
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    /// First element with a given @id, for @itemref.
+    ids: HashMap<&'a str, usize>,
+}
+
+impl<'a, E: Element<'a>> Tree<'a, E> {
+    fn new(root: &E) -> Self {
+        let mut tree = Tree {
+            elements: vec![],
+            parents: vec![],
+            children: vec![],
+            ids: HashMap::new(),
+        };
+        tree.push(*root, None);
+        tree
+    }
+
+    fn push(&mut self, element: E, parent: Option<usize>) -> usize {
+        let index = self.elements.len();
+        self.elements.push(element);
+        self.parents.push(parent);
+        self.children.push(vec![]);
+        if let Some(id) = element.attr("id") {
+            self.ids.entry(id).or_insert(index);
+        }
+        for child in element.children() {
+            let child = self.push(child, Some(index));
+            self.children[index].push(child);
+        }
+        index
+    }
+
+    fn attr(&self, index: usize, name: &str) -> Option<&'a str> {
+        self.elements[index].attr(name)
+    }
+
+    fn is_item(&self, index: usize) -> bool {
+        self.attr(index, "itemscope").is_some()
+    }
+
+    fn property_names(&self, index: usize) -> Vec<&'a str> {
+        let mut names = vec![];
+        for name in self
+            .attr(index, "itemprop")
+            .unwrap_or("")
+            .split_whitespace()
+        {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Items that are not the value of a property.
+    fn top_level_items(&self) -> Vec<usize> {
+        (0..self.elements.len())
+            .filter(|i| self.is_item(*i) && self.attr(*i, "itemprop").is_none())
+            .collect()
+    }
+
+    /// Properties of an item in tree order, crawling its children and the elements of @itemref
+    /// down to the nested items (HTML, "the properties of an item").
+    fn properties(&self, item: usize) -> Vec<usize> {
+        let mut pending = self.children[item].clone();
+        for id in self.attr(item, "itemref").unwrap_or("").split_whitespace() {
+            pending.extend(self.ids.get(id));
+        }
+        let mut visited = HashSet::from([item]);
+        let mut properties = vec![];
+        while let Some(current) = pending.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            if !self.is_item(current) {
+                pending.extend(&self.children[current]);
+            }
+            if !self.property_names(current).is_empty() {
+                properties.push(current);
+            }
+        }
+        properties.sort_unstable();
+        properties
+    }
+
+    /// @lang of the element or of the closest ancestor.
+    fn lang(&self, mut index: usize) -> Option<&'a str> {
+        loop {
+            if let Some(lang) = self
+                .attr(index, "lang")
+                .or_else(|| self.attr(index, "xml:lang"))
+            {
+                return Some(lang).filter(|l| !l.is_empty());
+            }
+            index = self.parents[index]?;
+        }
+    }
+}
+
+/// Vocabulary in scope, from the type of the item or inherited by the items without @itemtype.
+#[derive(Default, Clone)]
+struct EvaluationContext<'a> {
+    current_vocabulary: Option<Cow<'a, str>>,
+}
+
+impl EvaluationContext<'_> {
+    fn rules(&self) -> Option<&'static VocabularyRules> {
+        let vocabulary = self.current_vocabulary.as_deref()?;
+        REGISTRY
+            .iter()
+            .find(|(prefix, _)| vocabulary.starts_with(prefix))
+            .map(|(_, rules)| rules)
+    }
+
+    /// An absolute name as is, otherwise appended to the vocabulary.
+    /// A name is ignored when there is no vocabulary.
+    fn predicate(&self, name: &str) -> Option<String> {
+        if is_absolute(name) {
+            return Some(name.to_string());
+        }
+        let vocabulary = self.current_vocabulary.as_deref()?;
+        if vocabulary.ends_with(['#', '/']) {
+            Some(format!("{vocabulary}{name}"))
+        } else {
+            Some(format!("{vocabulary}#{name}"))
+        }
+    }
+}
+
+struct Extractor<'a, 'b, E> {
+    tree: Tree<'a, E>,
+    base: &'b str,
+    sink: &'b mut dyn TripleSink<'a>,
+    bnode_ids: &'b mut dyn BlankNodeIdGenerator,
+    /// Subjects of the items already generated.
+    memory: HashMap<usize, Term<'a>>,
+}
+
+/// Stream the triples of the microdata items of the document, W3C Microdata to RDF.
+/// The document links its top-level items with an `md:item` list.
+pub(crate) fn parse_into<'a, E: Element<'a>>(
+    root: &E,
+    base: &str,
+    sink: &mut dyn TripleSink<'a>,
+    bnode_ids: &mut dyn BlankNodeIdGenerator,
+) {
+    let mut extractor = Extractor {
+        tree: Tree::new(root),
+        base,
+        sink,
+        bnode_ids,
+        memory: HashMap::new(),
+    };
+    let items = extractor
+        .tree
+        .top_level_items()
+        .into_iter()
+        .map(|item| extractor.generate_triples(item, &EvaluationContext::default()))
+        .collect::<Vec<_>>();
+    if !items.is_empty() {
+        let list = extractor.list(items);
+        let base = iri(Cow::Owned(base.to_string()));
+        extractor
+            .sink
+            .triple(base, iri(Cow::Borrowed(MD_ITEM)), list);
+    }
+}
+
+impl<'a, E: Element<'a>> Extractor<'a, '_, E> {
+    fn generate_triples(&mut self, item: usize, context: &EvaluationContext<'a>) -> Term<'a> {
+        if let Some(subject) = self.memory.get(&item) {
+            return subject.clone();
+        }
+        let subject = match self.tree.attr(item, "itemid") {
+            Some(id) => iri(self.resolve(id.trim())),
+            None => self.blank_node(),
+        };
+        self.memory.insert(item, subject.clone());
+
+        let types = self
+            .tree
+            .attr(item, "itemtype")
+            .unwrap_or("")
+            .split_whitespace()
+            .filter(|t| is_absolute(t))
+            .collect::<Vec<_>>();
+        for item_type in &types {
+            self.sink.triple(
+                subject.clone(),
+                iri(Cow::Borrowed(NS_TYPE)),
+                iri(Cow::Borrowed(item_type)),
+            );
+        }
+        let context = match types.first() {
+            Some(item_type) => EvaluationContext {
+                current_vocabulary: Some(vocabulary(item_type)),
+            },
+            None => context.clone(),
+        };
+        let rules = context.rules();
+
+        let mut lists: Vec<(String, Vec<Term<'a>>)> = vec![];
+        for property in self.tree.properties(item) {
+            let value = self.property_value(property, &context);
+            for name in self.tree.property_names(property) {
+                let Some(predicate) = context.predicate(name) else {
+                    continue;
+                };
+                if let Some(rules) = rules {
+                    for (_, super_property) in rules.sub_properties.iter().filter(|(p, _)| {
+                        *p == name || context.predicate(p).as_ref() == Some(&predicate)
+                    }) {
+                        // rdf:type only takes IRIs, e.g not the text of a <span>
+                        if *super_property == NS_TYPE && !matches!(value, Term::Iri(_)) {
+                            continue;
+                        }
+                        self.sink.triple(
+                            subject.clone(),
+                            iri(Cow::Borrowed(super_property)),
+                            value.clone(),
+                        );
+                    }
+                }
+                if rules.is_some_and(|r| r.list) {
+                    match lists.iter_mut().find(|(p, _)| *p == predicate) {
+                        Some((_, values)) => values.push(value.clone()),
+                        None => lists.push((predicate, vec![value.clone()])),
+                    }
+                } else {
+                    self.sink
+                        .triple(subject.clone(), iri(Cow::Owned(predicate)), value.clone());
+                }
+            }
+        }
+        for (predicate, values) in lists {
+            let list = self.list(values);
+            self.sink
+                .triple(subject.clone(), iri(Cow::Owned(predicate)), list);
+        }
+        subject
+    }
+
+    /// HTML, "the property value of a name-value pair", then typed as in Microdata to RDF.
+    fn property_value(&mut self, property: usize, context: &EvaluationContext<'a>) -> Term<'a> {
+        if self.tree.is_item(property) {
+            return self.generate_triples(property, context);
+        }
+        let element = self.tree.elements[property];
+        let url_attribute = match element.name() {
+            "a" | "area" | "link" => Some("href"),
+            "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => Some("src"),
+            "object" => Some("data"),
+            _ => None,
+        };
+        if let Some(attribute) = url_attribute {
+            return match element.attr(attribute) {
+                Some(url) => iri(self.resolve(url.trim())),
+                None => Term::Literal(Literal::new("")),
+            };
+        }
+        let lang = self.tree.lang(property);
+        match element.name() {
+            "meta" => text_literal(Cow::Borrowed(element.attr("content").unwrap_or("")), lang),
+            "data" | "meter" if element.attr("value").is_some() => {
+                number_literal(element.attr("value").unwrap_or(""), lang)
+            }
+            "time" => {
+                let value = element
+                    .attr("datetime")
+                    .map(Cow::Borrowed)
+                    .unwrap_or_else(|| Cow::Owned(element.texts().concat()));
+                match DataTypeFromPattern::date_time_from_pattern(&value)
+                    .and_then(|datatype| datatype.to_iri())
+                {
+                    Some(datatype) => Term::Literal(Literal::typed(value, datatype)),
+                    None => Term::Literal(Literal::new(value)),
+                }
+            }
+            _ => text_literal(Cow::Owned(element.texts().concat()), lang),
+        }
+    }
+
+    fn resolve(&self, reference: &'a str) -> Cow<'a, str> {
+        resolve_against_base(self.base, reference)
+    }
+
+    fn blank_node(&mut self) -> Term<'a> {
+        Term::BlankNode(BlankNode::new(self.bnode_ids.next_id()))
+    }
+
+    /// An rdf:List of the values, rdf:nil when empty.
+    fn list(&mut self, values: Vec<Term<'a>>) -> Term<'a> {
+        let mut head = iri(Cow::Borrowed(RDF_NIL));
+        for value in values.into_iter().rev() {
+            let node = self.blank_node();
+            self.sink
+                .triple(node.clone(), iri(Cow::Borrowed(RDF_FIRST)), value);
+            self.sink
+                .triple(node.clone(), iri(Cow::Borrowed(RDF_REST)), head);
+            head = node;
+        }
+        head
+    }
+}
+
+/// Vocabulary of a type that is not in the registry: the type up to its last `#` or `/`.
+fn vocabulary(item_type: &str) -> Cow<'_, str> {
+    if let Some((prefix, _)) = REGISTRY.iter().find(|(p, _)| item_type.starts_with(p)) {
+        return Cow::Borrowed(prefix);
+    }
+    let end = item_type
+        .rfind(['#', '/'])
+        .map_or(item_type.len(), |i| i + 1);
+    Cow::Borrowed(&item_type[..end])
+}
+
+fn is_absolute(value: &str) -> bool {
+    Url::parse(value).is_ok()
+}
+
+fn iri(value: Cow<'_, str>) -> Term<'_> {
+    Term::Iri(Iri::new(value))
+}
+
+fn text_literal<'a>(value: Cow<'a, str>, lang: Option<&'a str>) -> Term<'a> {
+    match lang {
+        Some(lang) => Term::Literal(Literal::lang_tagged(value, lang)),
+        None => Term::Literal(Literal::new(value)),
+    }
+}
+
+/// @value of `<data>` and `<meter>`, an xsd:integer or xsd:double when it is a number.
+fn number_literal<'a>(value: &'a str, lang: Option<&'a str>) -> Term<'a> {
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (
+            mantissa,
+            Some(exponent.strip_prefix(['+', '-']).unwrap_or(exponent)),
+        ),
+        None => (unsigned, None),
+    };
+    let mantissa_is_number = match mantissa.split_once('.') {
+        Some((int, frac)) => {
+            (digits(int) || int.is_empty())
+                && (digits(frac) || frac.is_empty())
+                && !(int.is_empty() && frac.is_empty())
+        }
+        None => digits(mantissa),
+    };
+    let datatype = if digits(unsigned) {
+        Some(XSD_INTEGER)
+    } else if mantissa_is_number && exponent.is_none_or(digits) {
+        Some(XSD_DOUBLE)
+    } else {
+        None
+    };
+    match datatype {
+        Some(datatype) => Term::Literal(Literal::typed(value, Iri::new(datatype))),
+        None => text_literal(Cow::Borrowed(value), lang),
+    }
+}
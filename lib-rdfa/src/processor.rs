@@ -0,0 +1,421 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use url::Url;
+
+use crate::{
+    BlankNodeIdGenerator, Element, InitialContext, RdfaError, RdfaGraph, TripleSink,
+    VocabularyResolver,
+    blank_node::{BlankNodeIdFactory, BlankNodes},
+    curie::{lowercase, prefix_name},
+    expansion::Vocabularies,
+    initial_context::host_language_context,
+    parse_into,
+    structs::Context,
+};
+
+/// The host language the document is processed as.
+/// It decides where the base comes from, which language attribute
+/// is read and whether html elements get a special treatment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HostLanguage {
+    #[default]
+    Html5,
+    Xhtml5,
+    Html4,
+    Xhtml1,
+    Svg,
+    /// Any other XML document.
+    Xml,
+}
+
+impl HostLanguage {
+    /// HTML+RDFa: `<base>`, `<head>` and `<body>` have a meaning.
+    pub(crate) fn is_html(self) -> bool {
+        matches!(
+            self,
+            HostLanguage::Html5 | HostLanguage::Xhtml5 | HostLanguage::Html4 | HostLanguage::Xhtml1
+        )
+    }
+
+    /// XML-based languages honor @xml:base, XHTML1 only has `<base>`.
+    pub(crate) fn has_xml_base(self) -> bool {
+        matches!(
+            self,
+            HostLanguage::Xhtml5 | HostLanguage::Svg | HostLanguage::Xml
+        )
+    }
+
+    /// HTML+RDFa §3.1: with @property, the terms of @rel and @rev are ignored.
+    pub(crate) fn ignores_rel_terms_with_property(self) -> bool {
+        matches!(
+            self,
+            HostLanguage::Html5 | HostLanguage::Xhtml5 | HostLanguage::Html4
+        )
+    }
+
+    /// The terms of the XHTML vocabulary are part of the XHTML+RDFa 1.1 initial context,
+    /// HTML+RDFa only uses the one of RDFa Core (HTML+RDFa §3.1).
+    pub(crate) fn has_xhtml_vocabulary(self) -> bool {
+        matches!(self, HostLanguage::Xhtml1)
+    }
+
+    /// `<time>` and @datetime (HTML+RDFa §3.1), @value of `<data>` and `<meter>`.
+    pub(crate) fn has_value_elements(self) -> bool {
+        matches!(self, HostLanguage::Html5 | HostLanguage::Xhtml5)
+    }
+
+    /// @xml:lang takes precedence over @lang in XHTML, and is the only one in SVG and XML.
+    pub(crate) fn lang<'a>(self, element: &impl Element<'a>) -> Option<&'a str> {
+        match self {
+            HostLanguage::Html5 | HostLanguage::Html4 => {
+                element.attr("lang").or_else(|| element.attr("xml:lang"))
+            }
+            HostLanguage::Xhtml5 | HostLanguage::Xhtml1 => {
+                element.attr("xml:lang").or_else(|| element.attr("lang"))
+            }
+            HostLanguage::Svg | HostLanguage::Xml => element.attr("xml:lang"),
+        }
+    }
+}
+
+/// The RDFa rules the document is processed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RdfaVersion {
+    /// No @vocab, @prefix nor @inlist, prefixes come from `xmlns:`
+    /// and mixed content is an XML literal.
+    Rdfa10,
+    #[default]
+    Rdfa11,
+}
+
+impl RdfaVersion {
+    /// From @version of the root element, e.g `XHTML+RDFa 1.0`.
+    pub(crate) fn detect<'a>(root: &impl Element<'a>) -> Self {
+        match root.attr("version") {
+            Some(version) if version.contains("RDFa 1.0") => RdfaVersion::Rdfa10,
+            _ => RdfaVersion::Rdfa11,
+        }
+    }
+}
+
+/// Options of the processor. Use the builder methods and call `build`
+/// to get a validated `RdfaProcessor`.
+///
+/// ```
+/// use graph_rdfa_processor::ParserOptions;
+/// let processor = ParserOptions::default()
+///     .base("http://example.com/")
+///     .prefix("foaf", "http://xmlns.com/foaf/0.1/")
+///     .build()
+///     .unwrap();
+/// let graph = processor
+///     .parse_str(r#"<p about="me" property="foaf:name">Nordine</p>"#)
+///     .unwrap();
+/// assert_eq!(
+///     "<http://example.com/me> <http://xmlns.com/foaf/0.1/name> \"Nordine\" .\n",
+///     graph.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParserOptions {
+    base: String,
+    well_known_prefix: Option<String>,
+    prefixes: HashMap<String, String>,
+    vocab: Option<String>,
+    lang: Option<String>,
+    host_language: Option<HostLanguage>,
+    version: Option<RdfaVersion>,
+    processor_graph: bool,
+    microdata: bool,
+    empty_ref_node_substitute: Option<String>,
+    blank_node_ids: BlankNodeIdFactory,
+    vocabularies: Option<Vocabularies>,
+    initial_context: Option<InitialContext>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RdfaProcessor {
+    options: ParserOptions,
+}
+
+impl ParserOptions {
+    /// Base IRI of the document. Required, must be absolute.
+    pub fn base(mut self, base: impl Into<String>) -> Self {
+        self.base = base.into();
+        self
+    }
+    /// Skolemize blank nodes as `<{well_known_prefix}{id}>`.
+    pub fn well_known_prefix(mut self, well_known_prefix: impl Into<String>) -> Self {
+        self.well_known_prefix = Some(well_known_prefix.into());
+        self
+    }
+    /// Prefix in scope for the whole document, unless redefined by a @prefix attribute.
+    pub fn prefix(mut self, prefix: impl Into<String>, iri: impl Into<String>) -> Self {
+        self.prefixes.insert(prefix.into(), iri.into());
+        self
+    }
+    /// Vocabulary used for terms when the document doesn't set @vocab.
+    pub fn vocab(mut self, vocab: impl Into<String>) -> Self {
+        self.vocab = Some(vocab.into());
+        self
+    }
+    /// Language of literals when the document doesn't set @lang.
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+    /// `HostLanguage::Html5` for `parse_str`, `HostLanguage::Xml` for `parse_xml_str` when not set.
+    pub fn host_language(mut self, host_language: HostLanguage) -> Self {
+        self.host_language = Some(host_language);
+        self
+    }
+    /// Force the RDFa version, detected from @version of the root element otherwise.
+    pub fn version(mut self, version: RdfaVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+    /// Also collect the processor graph, see `RdfaGraph::processor_graph`.
+    pub fn processor_graph(mut self, processor_graph: bool) -> Self {
+        self.processor_graph = processor_graph;
+        self
+    }
+    /// Also extract the HTML microdata items (`itemscope`, `itemprop`...), W3C Microdata to RDF.
+    pub fn microdata(mut self, microdata: bool) -> Self {
+        self.microdata = microdata;
+        self
+    }
+    /// Identifier of the blank node for `[_:]`. A new blank node for every parse when not provided.
+    pub fn empty_ref_node_substitute(mut self, id: impl Into<String>) -> Self {
+        self.empty_ref_node_substitute = Some(id.into());
+        self
+    }
+    /// How blank node identifiers are generated, e.g `SequentialIdGenerator::default`
+    /// for reproducible output. A new generator is created for every parse.
+    pub fn blank_node_ids<G, F>(mut self, new_generator: F) -> Self
+    where
+        G: BlankNodeIdGenerator + 'static,
+        F: Fn() -> G + Send + Sync + 'static,
+    {
+        self.blank_node_ids = BlankNodeIdFactory::new(new_generator);
+        self
+    }
+
+    /// Expand the vocabularies referenced by @vocab (RDFa Core §10),
+    /// loading them with the resolver instead of the network.
+    pub fn vocabulary_expansion(mut self, resolver: impl VocabularyResolver + 'static) -> Self {
+        self.vocabularies = Some(Vocabularies(Arc::new(resolver)));
+        self
+    }
+    /// Prefixes and terms in scope before any declaration, replacing the W3C initial context
+    /// of the host language. `InitialContext::empty()` only accepts what the document declares.
+    pub fn initial_context(mut self, initial_context: InitialContext) -> Self {
+        self.initial_context = Some(initial_context);
+        self
+    }
+
+    pub fn get_base(&self) -> &str {
+        &self.base
+    }
+    pub fn get_well_known_prefix(&self) -> Option<&str> {
+        self.well_known_prefix.as_deref()
+    }
+    pub fn get_prefixes(&self) -> &HashMap<String, String> {
+        &self.prefixes
+    }
+    pub fn get_vocab(&self) -> Option<&str> {
+        self.vocab.as_deref()
+    }
+    pub fn get_lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+    /// None when it depends on the parse method.
+    pub fn get_host_language(&self) -> Option<HostLanguage> {
+        self.host_language
+    }
+    pub fn get_version(&self) -> Option<RdfaVersion> {
+        self.version
+    }
+    pub fn has_processor_graph(&self) -> bool {
+        self.processor_graph
+    }
+    pub fn has_microdata(&self) -> bool {
+        self.microdata
+    }
+    pub fn has_vocabulary_expansion(&self) -> bool {
+        self.vocabularies.is_some()
+    }
+    /// None when the initial context of the host language is used.
+    pub fn get_initial_context(&self) -> Option<&InitialContext> {
+        self.initial_context.as_ref()
+    }
+
+    pub fn build(self) -> Result<RdfaProcessor, RdfaError> {
+        fn invalid(option: &str, value: &str, reason: &str) -> RdfaError {
+            RdfaError::InvalidOption {
+                option: option.to_string(),
+                value: value.to_string(),
+                reason: reason.to_string(),
+            }
+        }
+        fn check_iri(option: &str, value: &str) -> Result<(), RdfaError> {
+            Url::parse(value)
+                .map(|_| ())
+                .map_err(|e| invalid(option, value, &e.to_string()))
+        }
+
+        check_iri("base", &self.base)?;
+        if let Some(well_known_prefix) = &self.well_known_prefix {
+            check_iri("well_known_prefix", well_known_prefix)?;
+        }
+        if let Some(vocab) = &self.vocab {
+            check_iri("vocab", vocab)?;
+        }
+        for (prefix, iri) in &self.prefixes {
+            if prefix_name(prefix).is_none() {
+                return Err(invalid("prefix", prefix, "not a valid prefix name"));
+            }
+            check_iri("prefix", iri)?;
+        }
+        // the contexts of the host languages are valid
+        if let Some(initial_context) = &self.initial_context {
+            for (prefix, iri) in initial_context.prefixes() {
+                if !prefix.is_empty() && prefix_name(prefix).is_none() {
+                    return Err(invalid(
+                        "initial_context",
+                        prefix,
+                        "not a valid prefix name",
+                    ));
+                }
+                check_iri("initial_context", iri)?;
+            }
+            for (term, iri) in initial_context.terms() {
+                if term.is_empty() || term.contains(|c: char| c == ':' || c.is_whitespace()) {
+                    return Err(invalid("initial_context", term, "not a valid term"));
+                }
+                check_iri("initial_context", iri)?;
+            }
+        }
+        if self.lang.as_ref().filter(|l| l.trim().is_empty()).is_some() {
+            return Err(invalid("lang", "", "language cannot be empty"));
+        }
+        if self
+            .empty_ref_node_substitute
+            .as_ref()
+            .is_some_and(|id| id.is_empty())
+        {
+            return Err(invalid(
+                "empty_ref_node_substitute",
+                "",
+                "substitute cannot be empty",
+            ));
+        }
+        Ok(RdfaProcessor { options: self })
+    }
+}
+
+impl RdfaProcessor {
+    pub fn builder() -> ParserOptions {
+        ParserOptions::default()
+    }
+
+    pub fn options(&self) -> &ParserOptions {
+        &self.options
+    }
+
+    /// Parse a document as `HostLanguage::Html5` unless the host language is set.
+    pub fn parse<'a>(&'a self, input: &impl Element<'a>) -> Result<RdfaGraph<'a>, RdfaError> {
+        self.parse_as(input, HostLanguage::Html5)
+    }
+
+    /// Stream the triples to the sink instead of collecting them in a graph.
+    /// Triples are not deduplicated. Returns the processor graph when enabled.
+    pub fn parse_into<'a>(
+        &'a self,
+        input: &impl Element<'a>,
+        sink: &mut impl TripleSink<'a>,
+    ) -> Result<Option<RdfaGraph<'a>>, RdfaError> {
+        parse_into(
+            input,
+            self.initial_context(input, HostLanguage::Html5),
+            sink,
+            &mut self.blank_nodes(),
+        )
+    }
+
+    /// Parse an html document. The graph doesn't borrow from the html nor the processor.
+    pub fn parse_str(&self, html: &str) -> Result<RdfaGraph<'static>, RdfaError> {
+        let document = scraper::Html::parse_document(html);
+        self.parse(&document.root_element()).map(|g| g.into_owned())
+    }
+
+    /// Parse a well-formed xml document, e.g SVG or Atom, as `HostLanguage::Xml`
+    /// unless the host language is set. Prefixes declared with `xmlns:` are in scope.
+    pub fn parse_xml_str(&self, xml: &str) -> Result<RdfaGraph<'static>, RdfaError> {
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document = roxmltree::Document::parse_with_options(xml, options).map_err(|e| {
+            RdfaError::InvalidXml {
+                reason: e.to_string(),
+            }
+        })?;
+        self.parse_as(&document.root_element(), HostLanguage::Xml)
+            .map(|g| g.into_owned())
+    }
+
+    fn parse_as<'a>(
+        &'a self,
+        input: &impl Element<'a>,
+        default_host_language: HostLanguage,
+    ) -> Result<RdfaGraph<'a>, RdfaError> {
+        RdfaGraph::parse(
+            input,
+            self.initial_context(input, default_host_language),
+            &mut self.blank_nodes(),
+        )
+    }
+
+    /// Blank nodes are not shared between parses.
+    fn blank_nodes(&self) -> BlankNodes {
+        BlankNodes::new(
+            self.options.blank_node_ids.generator(),
+            self.options.empty_ref_node_substitute.clone(),
+        )
+    }
+
+    fn initial_context<'a>(
+        &'a self,
+        input: &impl Element<'a>,
+        default_host_language: HostLanguage,
+    ) -> Context<'a> {
+        let options = &self.options;
+        let host_language = options.host_language.unwrap_or(default_host_language);
+        Context {
+            base: Cow::Borrowed(&options.base),
+            well_known_prefix: options.well_known_prefix.as_deref(),
+            vocab: options.vocab.as_deref().map(Cow::Borrowed),
+            lang: options.lang.as_deref(),
+            prefixes: options
+                .prefixes
+                .iter()
+                .map(|(k, v)| (lowercase(k), v.as_str()))
+                .collect(),
+            processor_graph: options.processor_graph,
+            microdata: options.microdata,
+            vocabularies: options.vocabularies.as_ref(),
+            // the one of the options, otherwise the one of the host language
+            initial_context: Some(
+                options
+                    .initial_context
+                    .as_ref()
+                    .unwrap_or_else(|| host_language_context(host_language)),
+            ),
+            host_language,
+            version: options
+                .version
+                .unwrap_or_else(|| RdfaVersion::detect(input)),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::borrow::Cow;
+
+use crate::{
+    BlankNodeIdGenerator, Element, RdfaError,
+    constants::{
+        DC_DESCRIPTION, NS_TYPE, PTR_EXPRESSION, PTR_XPATH_POINTER, RDFA_CONTEXT,
+        RDFA_DOCUMENT_ERROR, RDFA_ERROR, RDFA_PREFIX_REDEFINITION, RDFA_UNRESOLVED_CURIE,
+        RDFA_UNRESOLVED_TERM, RDFA_WARNING,
+    },
+    structs::RdfaGraph,
+    term::{BlankNode, Iri, Literal, Statement},
+};
+
+/// Collects the processor graph (RDFa Core 1.1 §7.6) while traversing the document.
+/// When disabled, reports are dropped.
+#[derive(Debug, Default)]
+pub(crate) struct ProcessorGraph<'a> {
+    statements: Option<Vec<Statement<'a>>>,
+}
+
+impl<'a> ProcessorGraph<'a> {
+    pub(crate) fn new(enabled: bool) -> Self {
+        ProcessorGraph {
+            statements: enabled.then(Vec::new),
+        }
+    }
+
+    /// Describe a value the processor ignored, e.g an unresolved CURIE.
+    pub(crate) fn report(
+        &mut self,
+        error: &RdfaError,
+        element: &impl Element<'a>,
+        bnode_ids: &mut dyn BlankNodeIdGenerator,
+    ) {
+        let Some(statements) = self.statements.as_mut() else {
+            return;
+        };
+        let message = BlankNode::new(bnode_ids.next_id());
+        let pointer = BlankNode::new(bnode_ids.next_id());
+        statements.extend(
+            classes(error)
+                .into_iter()
+                .map(|class| Statement::new(message.clone(), Iri::new(NS_TYPE), Iri::new(class))),
+        );
+        statements.extend([
+            Statement::new(
+                message.clone(),
+                Iri::new(DC_DESCRIPTION),
+                Literal::new(error.to_string()),
+            ),
+            Statement::new(message, Iri::new(RDFA_CONTEXT), pointer.clone()),
+            Statement::new(
+                pointer.clone(),
+                Iri::new(NS_TYPE),
+                Iri::new(PTR_XPATH_POINTER),
+            ),
+            Statement::new(
+                pointer,
+                Iri::new(PTR_EXPRESSION),
+                Literal::new(element.xpath()),
+            ),
+        ]);
+    }
+
+    pub(crate) fn into_graph(
+        self,
+        base: Cow<'a, str>,
+        well_known_prefix: Option<&'a str>,
+    ) -> Option<RdfaGraph<'a>> {
+        self.statements.map(|statements| RdfaGraph {
+            base,
+            well_known_prefix: well_known_prefix.map(Cow::Borrowed),
+            statements: statements.into_iter().collect(),
+            prefixes: Default::default(),
+            vocab: None,
+            processor_graph: None,
+        })
+    }
+}
+
+/// Class of the message and its rdfa:Warning / rdfa:Error super class.
+/// Only a document that cannot be processed is an error.
+fn classes(error: &RdfaError) -> Vec<&'static str> {
+    match error {
+        RdfaError::UnresolvedCurie { .. } => vec![RDFA_UNRESOLVED_CURIE, RDFA_WARNING],
+        RdfaError::UnresolvedTerm { .. } => vec![RDFA_UNRESOLVED_TERM, RDFA_WARNING],
+        RdfaError::PrefixRedefinition { .. } => vec![RDFA_PREFIX_REDEFINITION, RDFA_WARNING],
+        RdfaError::InvalidXml { .. } => vec![RDFA_DOCUMENT_ERROR, RDFA_ERROR],
+        _ => vec![RDFA_WARNING],
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use crate::{
+    constants::TERM_NS_TYPE,
+    structs::RdfaGraph,
+    term::{Literal, Statement, Term},
+};
+
+type Positions<'g, 'a> = HashMap<&'g Term<'a>, HashMap<&'g Term<'a>, Vec<usize>>>;
+
+/// SPO, POS and OSP indexes of a graph, for many queries on the same graph.
+/// Borrows the triples of the graph, so it cannot go stale, see `RdfaGraph::index`.
+#[derive(Debug)]
+pub struct TripleIndex<'g, 'a> {
+    triples: Vec<&'g Statement<'a>>,
+    spo: Positions<'g, 'a>,
+    pos: Positions<'g, 'a>,
+    osp: Positions<'g, 'a>,
+}
+
+impl<'g, 'a> TripleIndex<'g, 'a> {
+    fn new(graph: &'g RdfaGraph<'a>) -> Self {
+        let mut index = TripleIndex {
+            triples: graph.statements.iter().collect(),
+            spo: HashMap::new(),
+            pos: HashMap::new(),
+            osp: HashMap::new(),
+        };
+        // results come in a stable order
+        index.triples.sort();
+        for (position, stmt) in index.triples.iter().enumerate() {
+            let (s, p, o) = (&stmt.subject, &stmt.predicate, &stmt.object);
+            insert(&mut index.spo, s, p, position);
+            insert(&mut index.pos, p, o, position);
+            insert(&mut index.osp, o, s, position);
+        }
+        index
+    }
+
+    /// Triples matching the pattern, `None` matches any term.
+    pub fn triples_matching(
+        &self,
+        subject: Option<&Term>,
+        predicate: Option<&Term>,
+        object: Option<&Term>,
+    ) -> impl Iterator<Item = &'g Statement<'a>> {
+        self.candidates(subject, predicate, object)
+            .into_iter()
+            .map(|position| self.triples[position])
+            .filter(move |stmt| object.is_none_or(|o| &stmt.object == o))
+    }
+
+    pub fn objects(&self, subject: &Term, predicate: &Term) -> impl Iterator<Item = &'g Term<'a>> {
+        self.triples_matching(Some(subject), Some(predicate), None)
+            .map(|stmt| &stmt.object)
+    }
+
+    pub fn subjects(&self, predicate: &Term, object: &Term) -> impl Iterator<Item = &'g Term<'a>> {
+        self.triples_matching(None, Some(predicate), Some(object))
+            .map(|stmt| &stmt.subject)
+    }
+
+    pub fn subjects_of_type(&self, class: &Term) -> impl Iterator<Item = &'g Term<'a>> {
+        self.subjects(&TERM_NS_TYPE, class)
+    }
+
+    /// First literal of the subject for the predicate, e.g its title.
+    pub fn value(&self, subject: &Term, predicate: &Term) -> Option<&'g Literal<'a>> {
+        self.objects(subject, predicate)
+            .find_map(|object| object.as_literal())
+    }
+
+    /// Positions of the triples that may match, picking the index from the bound terms.
+    fn candidates(
+        &self,
+        subject: Option<&Term>,
+        predicate: Option<&Term>,
+        object: Option<&Term>,
+    ) -> Vec<usize> {
+        let lookup = |positions: &Positions, first: &Term, second: Option<&Term>| {
+            let Some(by_first) = positions.get(first) else {
+                return vec![];
+            };
+            match second {
+                Some(second) => by_first.get(second).cloned().unwrap_or_default(),
+                None => {
+                    let mut all = by_first.values().flatten().copied().collect::<Vec<_>>();
+                    all.sort_unstable();
+                    all
+                }
+            }
+        };
+        match (subject, predicate, object) {
+            (Some(s), Some(p), _) => lookup(&self.spo, s, Some(p)),
+            (Some(s), None, None) => lookup(&self.spo, s, None),
+            (s, None, Some(o)) => lookup(&self.osp, o, s),
+            (None, Some(p), o) => lookup(&self.pos, p, o),
+            (None, None, None) => (0..self.triples.len()).collect(),
+        }
+    }
+}
+
+fn insert<'g, 'a>(
+    positions: &mut Positions<'g, 'a>,
+    first: &'g Term<'a>,
+    second: &'g Term<'a>,
+    position: usize,
+) {
+    positions
+        .entry(first)
+        .or_default()
+        .entry(second)
+        .or_default()
+        .push(position);
+}
+
+impl<'a> RdfaGraph<'a> {
+    /// Index the triples for many queries, e.g in a loop over subjects.
+    pub fn index(&self) -> TripleIndex<'_, 'a> {
+        TripleIndex::new(self)
+    }
+
+    /// Triples matching the pattern, `None` matches any term.
+    /// Scans the whole graph, see `index` to run many queries.
+    pub fn triples_matching(
+        &self,
+        subject: Option<&Term>,
+        predicate: Option<&Term>,
+        object: Option<&Term>,
+    ) -> impl Iterator<Item = &Statement<'a>> {
+        let mut triples = self
+            .statements
+            .iter()
+            .filter(|stmt| {
+                subject.is_none_or(|s| &stmt.subject == s)
+                    && predicate.is_none_or(|p| &stmt.predicate == p)
+                    && object.is_none_or(|o| &stmt.object == o)
+            })
+            .collect::<Vec<_>>();
+        // results come in a stable order
+        triples.sort();
+        triples.into_iter()
+    }
+
+    pub fn objects(&self, subject: &Term, predicate: &Term) -> impl Iterator<Item = &Term<'a>> {
+        self.triples_matching(Some(subject), Some(predicate), None)
+            .map(|stmt| &stmt.object)
+    }
+
+    pub fn subjects(&self, predicate: &Term, object: &Term) -> impl Iterator<Item = &Term<'a>> {
+        self.triples_matching(None, Some(predicate), Some(object))
+            .map(|stmt| &stmt.subject)
+    }
+
+    pub fn subjects_of_type(&self, class: &Term) -> impl Iterator<Item = &Term<'a>> {
+        self.subjects(&TERM_NS_TYPE, class)
+    }
+
+    /// First literal of the subject for the predicate, e.g its title.
+    pub fn value(&self, subject: &Term, predicate: &Term) -> Option<&Literal<'a>> {
+        self.objects(subject, predicate)
+            .find_map(|object| object.as_literal())
+    }
+}
@@ -1,11 +1,10 @@
-use std::error::Error;
+use std::borrow::Cow;
 
-use scraper::{ElementRef, Selector, node::Element};
+use crate::{Element, HostLanguage, RdfaError, RdfaVersion, c14n};
 
 #[derive(Debug, Clone, Copy)]
-pub struct RdfaElement<'a, 'b> {
-    pub element_ref: &'b ElementRef<'a>,
-    pub element: &'a Element,
+pub struct RdfaElement<'a, 'b, E> {
+    pub element: &'b E,
     pub name: &'a str,
     pub base: Option<&'a str>,
     pub vocab: Option<&'a str>,
@@ -22,40 +21,51 @@ pub struct RdfaElement<'a, 'b> {
     pub content: Option<&'a str>,
     pub datatype: Option<&'a str>,
     pub datetime: Option<&'a str>,
+    pub value: Option<&'a str>,
     pub resource: Option<&'a str>,
+    pub host_language: HostLanguage,
+    pub version: RdfaVersion,
 }
 
 #[allow(unused)]
-impl<'a, 'b> RdfaElement<'a, 'b> {
-    pub fn new(element_ref: &'b ElementRef<'a>) -> Result<Self, Box<dyn Error>> {
-        let element = element_ref.value();
+impl<'a, 'b, E: Element<'a>> RdfaElement<'a, 'b, E> {
+    pub fn new(
+        element: &'b E,
+        host_language: HostLanguage,
+        version: RdfaVersion,
+    ) -> Result<Self, RdfaError> {
         let vocab = element.attr("vocab").map(|v| v.trim());
-        let base = element_ref
-            .select(&Selector::parse("base")?)
-            .next()
-            .and_then(|e| e.attr("href"))
-            .map(|b| {
-                let pos_fragment = b.chars().position(|p| p == '#').unwrap_or(b.len());
-                &b[0..pos_fragment]
-            });
+        let xml_base = element
+            .attr("xml:base")
+            .filter(|_| host_language.has_xml_base());
+        // the `<base>` element of html is looked up once for the whole document
+        let base = xml_base.map(without_fragment);
         let name = element.name();
         let prefix = element.attr("prefix");
         let resource = element.attr("resource");
-        let lang = element.attr("lang").or_else(|| element.attr("xml:lang"));
+        let lang = host_language.lang(element);
         let property = element.attr("property");
         let rel = element.attr("rel");
         let rev = element.attr("rev");
         let type_of = element.attr("typeof");
-        let src = element.attr("src");
+        // the resource of an embedded object is its @data
+        let src = element.attr("src").or_else(|| {
+            element
+                .attr("data")
+                .filter(|_| host_language.is_html() && version == RdfaVersion::Rdfa11)
+                .filter(|_| name == "object")
+        });
         let href = element.attr("href");
         let datatype = element.attr("datatype");
         let inlist = element.attr("inlist");
         let content = element.attr("content");
         let about = element.attr("about");
         let datetime = element.attr("datetime");
+        let value = element
+            .attr("value")
+            .filter(|_| host_language.has_value_elements() && matches!(name, "data" | "meter"));
 
-        Ok(Self {
-            element_ref,
+        let elt = Self {
             element,
             base,
             name,
@@ -73,10 +83,56 @@ impl<'a, 'b> RdfaElement<'a, 'b> {
             content,
             datatype,
             datetime,
+            value,
             resource,
+            host_language,
+            version,
+        };
+        Ok(match version {
+            RdfaVersion::Rdfa10 => elt.rdfa_10(),
+            RdfaVersion::Rdfa11 => elt.ignore_rel_and_rev_terms(),
         })
     }
 
+    /// @rel and @rev made of terms only are treated as absent, see `is_ignored_term`.
+    fn ignore_rel_and_rev_terms(mut self) -> Self {
+        let only_terms = |v: &str| v.split_whitespace().all(|t| !t.contains(':'));
+        if self.ignores_rel_terms() {
+            self.rel = self.rel.filter(|r| !only_terms(r));
+            self.rev = self.rev.filter(|r| !only_terms(r));
+        }
+        self
+    }
+
+    fn ignores_rel_terms(&self) -> bool {
+        self.host_language.ignores_rel_terms_with_property()
+            && self.version == RdfaVersion::Rdfa11
+            && self.property.is_some()
+    }
+
+    /// A term of @rel or @rev on an element with @property, HTML+RDFa §3.1.
+    pub fn is_ignored_term(&self, attribute: &str, value: &str) -> bool {
+        matches!(attribute, "rel" | "rev") && self.ignores_rel_terms() && !value.contains(':')
+    }
+
+    /// RDFa 1.0 has no @vocab, @prefix nor @inlist. @src sets the new subject like @about,
+    /// so do @resource and @href without @rel or @rev, otherwise they are the object.
+    fn rdfa_10(mut self) -> Self {
+        self.vocab = None;
+        self.prefix = None;
+        self.inlist = None;
+        if self.has_rel_or_rev() {
+            self.about = self.about.or(self.src);
+            self.resource = self.resource.or(self.href);
+        } else {
+            self.about = self.about.or(self.src).or(self.resource).or(self.href);
+            self.resource = None;
+        }
+        self.src = None;
+        self.href = None;
+        self
+    }
+
     pub fn has_no_rel_and_no_property(&self) -> bool {
         self.rel.is_none() && self.property.is_none()
     }
@@ -112,22 +168,42 @@ impl<'a, 'b> RdfaElement<'a, 'b> {
     pub fn has_about(&self) -> bool {
         self.about.is_some()
     }
-    pub fn get_time(&self) -> Option<&'a str> {
-        if self.element.name() == "time" || self.datetime.is_some() {
-            self.datetime
-                .or_else(|| self.element_ref.text().take(1).last())
+    /// @datetime, or the text of a `<time>`, typed by its lexical form.
+    pub fn get_time(&self) -> Option<Cow<'a, str>> {
+        if !self.host_language.has_value_elements() || self.version == RdfaVersion::Rdfa10 {
+            None
+        } else if let Some(datetime) = self.datetime {
+            Some(Cow::Borrowed(datetime))
+        } else if self.name == "time" {
+            Some(match self.texts().as_slice() {
+                [text] => Cow::Borrowed(*text),
+                texts => Cow::Owned(texts.concat()),
+            })
         } else {
             None
         }
     }
+    /// @value of `<data>` and `<meter>`, a plain literal like @content.
+    pub fn get_value(&self) -> Option<&'a str> {
+        self.value.filter(|_| self.version == RdfaVersion::Rdfa11)
+    }
     pub fn texts(&self) -> Vec<&'a str> {
-        self.element_ref.text().collect()
+        self.element.texts()
     }
     pub fn inner_html(&self) -> String {
-        self.element_ref.inner_html()
+        self.element.inner_html()
+    }
+    /// Canonical XML of the content, see `c14n::xml_literal`.
+    pub fn xml_literal(&self) -> String {
+        c14n::xml_literal(self.element)
     }
 
     pub(crate) fn has_rel_or_rev(&self) -> bool {
         self.rel.is_some() || self.rev.is_some()
     }
 }
+
+/// A base is used without its fragment.
+pub(crate) fn without_fragment(base: &str) -> &str {
+    base.split_once('#').map_or(base, |(base, _)| base)
+}
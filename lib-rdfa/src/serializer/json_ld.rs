@@ -0,0 +1,233 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use serde_json::{Map, Value, json};
+
+use super::{Index, key};
+use crate::{
+    constants::{NS_TYPE, RDF_NIL},
+    structs::RdfaGraph,
+    term::Term,
+};
+
+/// Shape of the JSON-LD document, see `RdfaGraph::to_json_ld`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonLdForm {
+    /// An array of node objects with full IRIs.
+    #[default]
+    Expanded,
+    /// IRIs compacted with a context made of the @prefix and @vocab of the document.
+    Compacted,
+}
+
+impl RdfaGraph<'_> {
+    /// Serialize the graph as JSON-LD 1.1. One node object per subject,
+    /// rdf:first/rdf:rest chains are written as `@list`.
+    pub fn to_json_ld(&self, form: JsonLdForm) -> Value {
+        JsonLdWriter::new(self, form).write()
+    }
+}
+
+struct JsonLdWriter<'g> {
+    index: Index<'g>,
+    form: JsonLdForm,
+    vocab: Option<&'g str>,
+    prefixes: Vec<(&'g str, &'g str)>,
+    used_prefixes: BTreeSet<&'g str>,
+    uses_vocab: bool,
+}
+
+impl<'g> JsonLdWriter<'g> {
+    fn new(graph: &'g RdfaGraph<'g>, form: JsonLdForm) -> Self {
+        let mut prefixes = graph
+            .prefixes
+            .iter()
+            .map(|(name, ns)| (name.as_ref(), ns.as_ref()))
+            // a compact IRI only expands with a prefix ending with a gen-delim
+            .filter(|(name, ns)| {
+                !name.is_empty()
+                    && !name.contains(':')
+                    && ns.ends_with([':', '/', '?', '#', '[', ']', '@'])
+            })
+            .collect::<Vec<_>>();
+        prefixes.sort();
+        JsonLdWriter {
+            index: Index::new(graph),
+            form,
+            vocab: graph.vocab.as_deref().filter(|v| !v.is_empty()),
+            prefixes,
+            used_prefixes: BTreeSet::new(),
+            uses_vocab: false,
+        }
+    }
+
+    fn write(mut self) -> Value {
+        let subjects = self
+            .index
+            .subjects
+            .keys()
+            .filter(|k| !self.index.list_members.contains(*k))
+            .cloned()
+            .collect::<Vec<_>>();
+        let nodes = subjects
+            .iter()
+            .map(|subject| self.node_object(subject))
+            .collect::<Vec<_>>();
+        if self.form == JsonLdForm::Expanded {
+            return Value::Array(nodes);
+        }
+
+        let mut context = Map::new();
+        if let Some(vocab) = self.vocab.filter(|_| self.uses_vocab) {
+            context.insert("@vocab".into(), json!(vocab));
+        }
+        for name in &self.used_prefixes {
+            if let Some((_, ns)) = self.prefixes.iter().find(|(n, _)| n == name) {
+                context.insert(name.to_string(), json!(ns));
+            }
+        }
+        let mut document = Map::new();
+        if !context.is_empty() {
+            document.insert("@context".into(), Value::Object(context));
+        }
+        match nodes.as_slice() {
+            [Value::Object(node)] => document.extend(node.clone()),
+            _ => {
+                document.insert("@graph".into(), Value::Array(nodes));
+            }
+        }
+        Value::Object(document)
+    }
+
+    fn node_object(&mut self, subject_key: &str) -> Value {
+        let (subject, predicate_objects) = self.index.subjects[subject_key].clone();
+        let mut node = Map::new();
+        node.insert("@id".into(), json!(self.id(subject)));
+        let mut types = vec![];
+        let mut properties = BTreeMap::<_, Vec<_>>::new();
+        for (predicate, object) in predicate_objects {
+            if predicate.is_iri(NS_TYPE) && object.as_literal().is_none() {
+                types.push(json!(self.type_iri(object)));
+                continue;
+            }
+            let property = self.vocab_iri(&iri(predicate));
+            let value = self.value(object);
+            properties.entry(property).or_default().push(value);
+        }
+        if !types.is_empty() {
+            node.insert("@type".into(), self.array(types));
+        }
+        for (property, values) in properties {
+            node.insert(property, self.array(values));
+        }
+        Value::Object(node)
+    }
+
+    fn value(&mut self, object: &'g Term<'g>) -> Value {
+        match object {
+            Term::Literal(literal) => {
+                let value = literal.value();
+                if let Some(lang) = literal.lang() {
+                    json!({"@value": value, "@language": lang})
+                } else if let Some(datatype) = literal.datatype() {
+                    // rdf:HTML and rdf:XMLLiteral keep their datatype, the markup is a string
+                    json!({"@value": value, "@type": self.vocab_iri(datatype.as_str())})
+                } else if self.form == JsonLdForm::Compacted {
+                    json!(value)
+                } else {
+                    json!({ "@value": value })
+                }
+            }
+            node if node.is_iri(RDF_NIL) => json!({ "@list": [] }),
+            node => {
+                if let Some(list) = self.index.lists.remove(&key(node)) {
+                    let items = list
+                        .items
+                        .into_iter()
+                        .map(|item| self.value(item))
+                        .collect::<Vec<_>>();
+                    json!({ "@list": items })
+                } else {
+                    json!({ "@id": self.id(node) })
+                }
+            }
+        }
+    }
+
+    fn id(&mut self, term: &Term) -> String {
+        match term {
+            Term::BlankNode(bnode) => self.index.blank_id(bnode.id()),
+            term => self.compact_iri(&iri(term)),
+        }
+    }
+
+    fn type_iri(&mut self, term: &Term) -> String {
+        match term {
+            Term::BlankNode(_) => self.id(term),
+            term => self.vocab_iri(&iri(term)),
+        }
+    }
+
+    /// Properties and types can be terms of the vocab.
+    fn vocab_iri(&mut self, iri: &str) -> String {
+        if self.form == JsonLdForm::Compacted {
+            let term = self
+                .vocab
+                .and_then(|vocab| iri.strip_prefix(vocab))
+                .filter(|term| is_term(term) && !self.prefixes.iter().any(|(n, _)| n == term));
+            if let Some(term) = term {
+                self.uses_vocab = true;
+                return term.to_string();
+            }
+        }
+        self.compact_iri(iri)
+    }
+
+    /// Longest matching prefix wins.
+    fn compact_iri(&mut self, iri: &str) -> String {
+        if self.form == JsonLdForm::Expanded {
+            return iri.to_string();
+        }
+        let prefix = self
+            .prefixes
+            .iter()
+            .filter(|(_, ns)| {
+                iri.strip_prefix(ns)
+                    .filter(|local| !local.is_empty() && !local.starts_with("//"))
+                    .is_some()
+            })
+            .min_by_key(|(_, ns)| Reverse(ns.len()))
+            .copied();
+        match prefix {
+            Some((name, ns)) => {
+                self.used_prefixes.insert(name);
+                format!("{name}:{}", &iri[ns.len()..])
+            }
+            None => iri.to_string(),
+        }
+    }
+
+    /// Compacted documents drop the array around single values.
+    fn array(&self, mut values: Vec<Value>) -> Value {
+        if self.form == JsonLdForm::Compacted && values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        }
+    }
+}
+
+fn iri(term: &Term) -> String {
+    match term {
+        Term::Iri(iri) => iri.as_str().to_string(),
+        term => key(term),
+    }
+}
+
+fn is_term(term: &str) -> bool {
+    !term.is_empty()
+        && !term.starts_with('@')
+        && !term.contains([':', '/', '#', '?', '[', ']', '@'])
+}
@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    constants::{NS_TYPE, RDF_FIRST, RDF_NIL, RDF_REST},
+    structs::RdfaGraph,
+    term::Term,
+};
+
+mod json_ld;
+pub(crate) mod ntriples;
+mod rdf_xml;
+mod turtle;
+
+pub use json_ld::JsonLdForm;
+
+pub(crate) type PredicateObjects<'g> = Vec<(&'g Term<'g>, &'g Term<'g>)>;
+
+pub(crate) struct List<'g> {
+    pub(crate) members: Vec<String>,
+    pub(crate) items: Vec<&'g Term<'g>>,
+}
+
+/// Triples grouped by subject, shared by the serializers that nest blank nodes and lists.
+/// Skolemized blank nodes are not considered blank.
+pub(crate) struct Index<'g> {
+    pub(crate) well_known_prefix: Option<&'g str>,
+    /// sorted by subject, then rdf:type first, then predicate and object
+    pub(crate) subjects: BTreeMap<String, (&'g Term<'g>, PredicateObjects<'g>)>,
+    /// how many times a blank node is used as an object
+    pub(crate) references: HashMap<String, usize>,
+    /// keyed by the head of the list
+    pub(crate) lists: HashMap<String, List<'g>>,
+    pub(crate) list_members: HashSet<String>,
+}
+
+impl<'g> Index<'g> {
+    pub(crate) fn new(graph: &'g RdfaGraph<'g>) -> Self {
+        let mut index = Index {
+            well_known_prefix: graph.well_known_prefix.as_deref(),
+            subjects: BTreeMap::new(),
+            references: HashMap::new(),
+            lists: HashMap::new(),
+            list_members: HashSet::new(),
+        };
+
+        // sorted on the n-triples form
+        let mut triples = BTreeMap::new();
+        for stmt in &graph.statements {
+            let (subject, predicate, object) = (&stmt.subject, &stmt.predicate, &stmt.object);
+            let predicate_key = if predicate.is_iri(NS_TYPE) {
+                String::new()
+            } else {
+                key(predicate)
+            };
+            triples.insert(
+                (key(subject), predicate_key, key(object)),
+                (subject, predicate, object),
+            );
+        }
+        let mut rest_objects = HashSet::new();
+        for ((subject_key, _, object_key), (subject, predicate, object)) in triples {
+            index
+                .subjects
+                .entry(subject_key)
+                .or_insert_with(|| (subject, vec![]))
+                .1
+                .push((predicate, object));
+            if index.is_blank(object) {
+                *index.references.entry(object_key.clone()).or_default() += 1;
+                if predicate.is_iri(RDF_REST) {
+                    rest_objects.insert(object_key);
+                }
+            }
+        }
+
+        let heads = index
+            .subjects
+            .keys()
+            .filter(|k| !rest_objects.contains(*k))
+            .cloned()
+            .collect::<Vec<_>>();
+        for head in heads {
+            if let Some(list) = index.list(head.clone()) {
+                index.list_members.extend(list.members.iter().cloned());
+                index.lists.insert(head, list);
+            }
+        }
+        index
+    }
+
+    /// Follow rdf:rest from the head up to rdf:nil. Every node of the chain must be
+    /// a blank node referenced once, with one rdf:first and one rdf:rest.
+    fn list(&self, head: String) -> Option<List<'g>> {
+        let mut list = List {
+            members: vec![],
+            items: vec![],
+        };
+        let mut current = head;
+        loop {
+            if list.members.contains(&current) || !self.is_referenced_once(&current) {
+                return None;
+            }
+            let (_, predicate_objects) = self.subjects.get(&current)?;
+            let [(first, item), (rest, next)] = predicate_objects.as_slice() else {
+                return None;
+            };
+            if !first.is_iri(RDF_FIRST) || !rest.is_iri(RDF_REST) {
+                return None;
+            }
+            list.members.push(current);
+            list.items.push(item);
+            if next.is_iri(RDF_NIL) {
+                return Some(list);
+            }
+            if !self.is_blank(next) {
+                return None;
+            }
+            current = key(next);
+        }
+    }
+
+    pub(crate) fn is_blank(&self, term: &Term) -> bool {
+        self.well_known_prefix.is_none() && term.is_blank_node()
+    }
+
+    pub(crate) fn is_referenced_once(&self, key: &str) -> bool {
+        self.references.get(key) == Some(&1)
+    }
+
+    /// Subjects that are neither nested in another node nor part of a list.
+    pub(crate) fn roots(&self) -> Vec<String> {
+        self.subjects
+            .keys()
+            .filter(|k| !self.list_members.contains(*k) && !self.references.contains_key(*k))
+            .cloned()
+            .collect()
+    }
+
+    /// `_:id`, or `{well_known_prefix}id` when skolemized.
+    pub(crate) fn blank_id(&self, id: &str) -> String {
+        match self.well_known_prefix {
+            Some(well_known_prefix) => format!("{well_known_prefix}{id}"),
+            None => format!("_:{id}"),
+        }
+    }
+}
+
+pub(crate) fn key(term: &Term) -> String {
+    term.to_string()
+}
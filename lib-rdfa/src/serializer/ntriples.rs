@@ -0,0 +1,148 @@
+use std::fmt::{Display, Formatter, Write};
+
+use crate::{
+    iri::encode_disallowed,
+    structs::RdfaGraph,
+    term::{Literal, Statement, Term},
+};
+
+/// Canonical N-Triples, one statement per line, sorted.
+impl Display for RdfaGraph<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_ntriples())
+    }
+}
+
+impl RdfaGraph<'_> {
+    /// Serialize the graph as canonical N-Triples, one statement per line, sorted.
+    pub fn to_ntriples(&self) -> String {
+        self.lines(|s| s.as_ntriple_string(self.well_known_prefix.as_deref()))
+    }
+
+    /// Serialize the graph as N-Quads, in the named graph `graph_name` or the base of the document.
+    pub fn to_nquads(&self, graph_name: Option<&str>) -> String {
+        let graph_name = graph_name.unwrap_or(&self.base);
+        self.lines(|s| s.as_nquad_string(graph_name, self.well_known_prefix.as_deref()))
+    }
+
+    fn lines(&self, line: impl Fn(&Statement) -> String) -> String {
+        let mut lines = self.statements.iter().map(line).collect::<Vec<_>>();
+        lines.sort();
+        lines.into_iter().fold(String::new(), |mut out, line| {
+            out.push_str(&line);
+            out.push('\n');
+            out
+        })
+    }
+}
+
+impl Statement<'_> {
+    pub(crate) fn as_ntriple_string(&self, well_known_prefix: Option<&str>) -> String {
+        let Statement {
+            subject,
+            predicate,
+            object,
+        } = self;
+        format!(
+            "{} {} {} .",
+            subject.as_ntriple_string(well_known_prefix),
+            predicate.as_ntriple_string(well_known_prefix),
+            object.as_ntriple_string(well_known_prefix)
+        )
+    }
+
+    pub(crate) fn as_nquad_string(
+        &self,
+        graph_name: &str,
+        well_known_prefix: Option<&str>,
+    ) -> String {
+        let Statement {
+            subject,
+            predicate,
+            object,
+        } = self;
+        format!(
+            "{} {} {} {} .",
+            subject.as_ntriple_string(well_known_prefix),
+            predicate.as_ntriple_string(well_known_prefix),
+            object.as_ntriple_string(well_known_prefix),
+            iri_ref(graph_name)
+        )
+    }
+}
+
+impl Term<'_> {
+    pub(crate) fn as_ntriple_string(&self, well_known_prefix: Option<&str>) -> String {
+        match (self, well_known_prefix) {
+            (Term::Iri(iri), _) => iri_ref(iri.as_str()),
+            (Term::BlankNode(bnode), Some(well_known_prefix)) => {
+                iri_ref(&format!("{well_known_prefix}{}", bnode.id()))
+            }
+            (Term::BlankNode(bnode), None) => format!("_:{}", bnode.id()),
+            (Term::Literal(literal), _) => literal.as_ntriple_string(),
+        }
+    }
+}
+
+impl Literal<'_> {
+    /// Language tags are case-insensitive, the canonical form is lowercase.
+    pub(crate) fn as_ntriple_string(&self) -> String {
+        let mut s = string_literal(self.value());
+        if let Some(datatype) = self.datatype() {
+            s.push_str("^^");
+            s.push_str(&iri_ref(datatype.as_str()));
+        } else if let Some(lang) = self.lang() {
+            s.push('@');
+            s.push_str(&lang.to_lowercase());
+        }
+        s
+    }
+}
+
+/// IRIREF. UCHAR would only hide characters that are not allowed in an IRI,
+/// so they are percent-encoded instead, see `iri::encode_disallowed`.
+pub(crate) fn iri_ref(iri: &str) -> String {
+    format!("<{}>", encode_disallowed(iri))
+}
+
+/// STRING_LITERAL_QUOTE, with ECHAR for the characters that have one
+/// and UCHAR for the other control characters.
+pub(crate) fn string_literal(value: &str) -> String {
+    let mut s = String::with_capacity(value.len() + 2);
+    s.push('"');
+    for c in value.chars() {
+        match c {
+            '\u{08}' => s.push_str("\\b"),
+            '\t' => s.push_str("\\t"),
+            '\n' => s.push_str("\\n"),
+            '\u{0C}' => s.push_str("\\f"),
+            '\r' => s.push_str("\\r"),
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\u{00}'..='\u{1F}' | '\u{7F}' => {
+                let _ = write!(s, "\\u{:04X}", c as u32);
+            }
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::{iri_ref, string_literal};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            r#""line 1\nline 2\r\t\"quoted\" \\ \u0000\u007F\b\f é""#,
+            string_literal("line 1\nline 2\r\t\"quoted\" \\ \u{0}\u{7F}\u{8}\u{C} é")
+        );
+        assert_eq!("<http://example.org/a>", iri_ref("http://example.org/a"));
+        assert_eq!(
+            "<http://example.org/a%20b%3E%7Bc%7D%7C%5E%22>",
+            iri_ref("http://example.org/a b>{c}|^\"")
+        );
+    }
+}
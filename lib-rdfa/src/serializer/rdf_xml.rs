@@ -0,0 +1,217 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use super::Index;
+use crate::{
+    RdfaError,
+    constants::{COMMON_PREFIXES, RDF_XML_LITERAL},
+    error::ErrorLocation,
+    structs::RdfaGraph,
+    term::Term,
+};
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+impl RdfaGraph<'_> {
+    /// Serialize the graph as RDF/XML, one rdf:Description per subject.
+    /// A predicate that cannot be written as a QName, e.g ending with `/`, is an error.
+    pub fn to_rdf_xml(&self) -> Result<String, RdfaError> {
+        RdfXmlWriter::new(self).write()
+    }
+}
+
+struct RdfXmlWriter<'g> {
+    index: Index<'g>,
+    /// document prefixes first, then the common ones
+    prefixes: Vec<(String, &'g str)>,
+    /// namespace -> prefix, for the namespaces declared on rdf:RDF
+    namespaces: BTreeMap<&'g str, String>,
+}
+
+impl<'g> RdfXmlWriter<'g> {
+    fn new(graph: &'g RdfaGraph<'g>) -> Self {
+        let mut prefixes = graph
+            .prefixes
+            .iter()
+            .map(|(name, ns)| (name.to_string(), ns.as_ref()))
+            .collect::<Vec<_>>();
+        prefixes.sort();
+        let mut common_prefixes = COMMON_PREFIXES
+            .iter()
+            .filter(|(name, _)| !graph.prefixes.contains_key(**name))
+            .map(|(name, ns)| (name.to_string(), *ns))
+            .collect::<Vec<_>>();
+        common_prefixes.sort();
+        prefixes.extend(common_prefixes);
+        // xmlns:xml* is reserved
+        prefixes.retain(|(name, ns)| {
+            is_nc_name(name) && !name.to_lowercase().starts_with("xml") && !ns.is_empty()
+        });
+
+        RdfXmlWriter {
+            index: Index::new(graph),
+            prefixes,
+            namespaces: BTreeMap::from([(RDF, "rdf".to_string())]),
+        }
+    }
+
+    fn write(mut self) -> Result<String, RdfaError> {
+        let mut body = String::new();
+        let subjects = self.index.subjects.values().cloned().collect::<Vec<_>>();
+        for (subject, predicate_objects) in subjects {
+            body.push_str(&format!("  <rdf:Description {}>\n", self.subject(subject)));
+            for (predicate, object) in predicate_objects {
+                let element = self.qname(predicate).ok_or_else(|| RdfaError::InvalidIri {
+                    value: iri(predicate, &self.index),
+                    reason: "the predicate cannot be written as a QName in RDF/XML".to_string(),
+                    location: ErrorLocation::default(),
+                })?;
+                body.push_str("    ");
+                body.push_str(&self.property(&element, object));
+                body.push('\n');
+            }
+            body.push_str("  </rdf:Description>\n");
+        }
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<rdf:RDF");
+        for (ns, name) in &self.namespaces {
+            xml.push_str(&format!("\n    xmlns:{name}=\"{}\"", escape(ns, true)));
+        }
+        xml.push_str(">\n");
+        xml.push_str(&body);
+        xml.push_str("</rdf:RDF>\n");
+        Ok(xml)
+    }
+
+    fn subject(&self, subject: &Term) -> String {
+        match self.blank_id(subject) {
+            Some(id) => format!("rdf:nodeID=\"{}\"", escape(&id, true)),
+            None => format!("rdf:about=\"{}\"", escape(&iri(subject, &self.index), true)),
+        }
+    }
+
+    fn property(&self, element: &str, object: &Term) -> String {
+        match object {
+            Term::Literal(literal) => {
+                let value = literal.value();
+                match (literal.datatype(), literal.lang()) {
+                    // the value is already markup, unless it is not well-formed
+                    (Some(dt), _) if dt.as_str() == RDF_XML_LITERAL && is_well_formed(value) => {
+                        format!("<{element} rdf:parseType=\"Literal\">{value}</{element}>")
+                    }
+                    (Some(dt), _) => format!(
+                        "<{element} rdf:datatype=\"{}\">{}</{element}>",
+                        escape(dt.as_str(), true),
+                        escape(value, false)
+                    ),
+                    (None, Some(lang)) => format!(
+                        "<{element} xml:lang=\"{}\">{}</{element}>",
+                        escape(lang, true),
+                        escape(value, false)
+                    ),
+                    (None, None) => format!("<{element}>{}</{element}>", escape(value, false)),
+                }
+            }
+            object => match self.blank_id(object) {
+                Some(id) => format!("<{element} rdf:nodeID=\"{}\"/>", escape(&id, true)),
+                None => format!(
+                    "<{element} rdf:resource=\"{}\"/>",
+                    escape(&iri(object, &self.index), true)
+                ),
+            },
+        }
+    }
+
+    /// Blank nodes that are not skolemized. A nodeID is an NCName, ids may start with a digit.
+    fn blank_id(&self, term: &Term) -> Option<String> {
+        match term {
+            Term::BlankNode(bnode) if self.index.is_blank(term) => Some(format!("b{}", bnode.id())),
+            _ => None,
+        }
+    }
+
+    /// Split the predicate in a namespace and an NCName, preferring the known prefixes.
+    /// Other namespaces are declared as ns1, ns2, ...
+    fn qname(&mut self, predicate: &'g Term<'g>) -> Option<String> {
+        let predicate: &'g str = predicate.as_iri()?.as_str();
+        let prefix = self
+            .prefixes
+            .iter()
+            .filter(|(_, ns)| {
+                predicate
+                    .strip_prefix(ns)
+                    .filter(|local| is_nc_name(local))
+                    .is_some()
+            })
+            .min_by_key(|(_, ns)| Reverse(ns.len()))
+            .map(|(name, ns)| (Some(name.clone()), *ns));
+        let (name, ns) = match prefix {
+            Some(prefix) => prefix,
+            None => {
+                let split = predicate
+                    .char_indices()
+                    .map(|(i, _)| i)
+                    .find(|i| *i > 0 && is_nc_name(&predicate[*i..]))?;
+                (None, &predicate[..split])
+            }
+        };
+        let name = self.declare(ns, name);
+        Some(format!("{name}:{}", &predicate[ns.len()..]))
+    }
+
+    fn declare(&mut self, ns: &'g str, name: Option<String>) -> String {
+        if let Some(declared) = self.namespaces.get(ns) {
+            return declared.clone();
+        }
+        let is_free = |name: &str| !self.namespaces.values().any(|n| n == name);
+        let name = name.filter(|n| is_free(n)).unwrap_or_else(|| {
+            (1..)
+                .map(|i| format!("ns{i}"))
+                .find(|n| is_free(n))
+                .expect("infinite iterator")
+        });
+        self.namespaces.insert(ns, name.clone());
+        name
+    }
+}
+
+fn iri(term: &Term, index: &Index) -> String {
+    match term {
+        Term::Iri(iri) => iri.as_str().to_string(),
+        Term::BlankNode(bnode) => index.blank_id(bnode.id()),
+        Term::Literal(literal) => literal.value().to_string(),
+    }
+}
+
+/// Content of an element, namespace prefixes used in the markup must be declared in it.
+fn is_well_formed(content: &str) -> bool {
+    roxmltree::Document::parse(&format!("<literal>{content}</literal>")).is_ok()
+}
+
+fn is_nc_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .filter(|c| c.is_alphabetic() || *c == '_')
+        .is_some()
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Characters that are not allowed in XML 1.0 are dropped.
+fn escape(value: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\n' if attribute => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            '\t' if attribute => escaped.push_str("&#x9;"),
+            '\t' | '\n' => escaped.push(c),
+            '\u{0}'..='\u{1F}' | '\u{FFFE}' | '\u{FFFF}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
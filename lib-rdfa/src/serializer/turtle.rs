@@ -0,0 +1,272 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashSet},
+};
+
+use super::{
+    Index, List, PredicateObjects, key,
+    ntriples::{iri_ref, string_literal},
+};
+use crate::{
+    constants::{COMMON_PREFIXES, NS_TYPE},
+    structs::RdfaGraph,
+    term::Term,
+};
+
+const INDENT: &str = "    ";
+
+impl RdfaGraph<'_> {
+    /// Serialize the graph as Turtle, using the prefixes of the document and the common ones.
+    /// Blank nodes referenced once are nested as `[ ... ]`,
+    /// rdf:first/rdf:rest chains are written as `( ... )`.
+    pub fn to_turtle(&self) -> String {
+        TurtleWriter::new(self).write()
+    }
+
+    /// Same as `to_turtle`, in the named graph `graph_name` or the base of the document.
+    pub fn to_trig(&self, graph_name: Option<&str>) -> String {
+        TurtleWriter::new(self).write_trig(graph_name.unwrap_or(&self.base))
+    }
+}
+
+struct TurtleWriter<'g> {
+    index: Index<'g>,
+    /// document prefixes first, then the common ones
+    prefixes: Vec<(&'g str, &'g str)>,
+    used_prefixes: BTreeSet<&'g str>,
+    written: HashSet<String>,
+}
+
+impl<'g> TurtleWriter<'g> {
+    fn new(graph: &'g RdfaGraph<'g>) -> Self {
+        let mut prefixes = graph
+            .prefixes
+            .iter()
+            .map(|(name, ns)| (name.as_ref(), ns.as_ref()))
+            .collect::<Vec<_>>();
+        prefixes.sort();
+        let mut common_prefixes = COMMON_PREFIXES
+            .iter()
+            .filter(|(name, _)| !graph.prefixes.contains_key(**name))
+            .map(|(name, ns)| (*name, *ns))
+            .collect::<Vec<_>>();
+        common_prefixes.sort();
+        prefixes.extend(common_prefixes);
+        prefixes.retain(|(name, ns)| !ns.is_empty() && is_valid_prefix(name));
+
+        TurtleWriter {
+            index: Index::new(graph),
+            prefixes,
+            used_prefixes: BTreeSet::new(),
+            written: HashSet::new(),
+        }
+    }
+
+    fn write(mut self) -> String {
+        let body = self.write_body();
+        let mut turtle = self.write_prefixes();
+        turtle.push_str(&body);
+        turtle
+    }
+
+    fn write_trig(mut self, graph_name: &str) -> String {
+        let graph_name = self.iri(graph_name);
+        let body = self.write_body();
+        let mut trig = self.write_prefixes();
+        trig.push_str(&graph_name);
+        trig.push_str(" {\n");
+        for line in body.lines() {
+            if !line.is_empty() {
+                trig.push_str(INDENT);
+                trig.push_str(line);
+            }
+            trig.push('\n');
+        }
+        trig.push_str("}\n");
+        trig
+    }
+
+    fn write_body(&mut self) -> String {
+        let mut body = String::new();
+        for subject in self.index.roots() {
+            self.write_subject(&mut body, &subject);
+        }
+        // blank nodes only referenced by each other
+        let leftovers = self
+            .index
+            .subjects
+            .keys()
+            .filter(|k| !self.index.list_members.contains(*k))
+            .cloned()
+            .collect::<Vec<_>>();
+        for subject in leftovers {
+            if !self.written.contains(&subject) {
+                self.write_subject(&mut body, &subject);
+            }
+        }
+        body
+    }
+
+    /// Only the prefixes used by the body.
+    fn write_prefixes(&self) -> String {
+        let mut prefixes = String::new();
+        for name in &self.used_prefixes {
+            if let Some((_, ns)) = self.prefixes.iter().find(|(n, _)| n == name) {
+                prefixes.push_str(&format!("@prefix {name}: {} .\n", iri_ref(ns)));
+            }
+        }
+        if !prefixes.is_empty() {
+            prefixes.push('\n');
+        }
+        prefixes
+    }
+
+    fn write_subject(&mut self, out: &mut String, subject_key: &str) {
+        self.written.insert(subject_key.to_string());
+        let (subject, predicate_objects) = self.index.subjects[subject_key].clone();
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        if self.index.is_blank(subject) && !self.index.references.contains_key(subject_key) {
+            out.push_str("[]");
+        } else {
+            let subject = self.term(subject);
+            out.push_str(&subject);
+        }
+        out.push(' ');
+        self.write_predicate_objects(out, &predicate_objects, 1);
+        out.push_str(" .\n");
+    }
+
+    fn write_predicate_objects(
+        &mut self,
+        out: &mut String,
+        predicate_objects: &PredicateObjects<'g>,
+        depth: usize,
+    ) {
+        let mut previous: Option<&Term> = None;
+        for (predicate, object) in predicate_objects {
+            if previous == Some(predicate) {
+                out.push_str(", ");
+            } else {
+                if previous.is_some() {
+                    out.push_str(" ;\n");
+                    out.push_str(&INDENT.repeat(depth));
+                }
+                if predicate.is_iri(NS_TYPE) {
+                    out.push('a');
+                } else {
+                    let predicate = self.term(predicate);
+                    out.push_str(&predicate);
+                }
+                out.push(' ');
+            }
+            self.write_object(out, object, depth);
+            previous = Some(predicate);
+        }
+    }
+
+    fn write_object(&mut self, out: &mut String, object: &'g Term<'g>, depth: usize) {
+        let object_key = key(object);
+        if !self.index.is_blank(object)
+            || self.written.contains(&object_key)
+            || !self.index.is_referenced_once(&object_key)
+        {
+            let object = self.term(object);
+            out.push_str(&object);
+            return;
+        }
+        if let Some(List { members, items }) = self.index.lists.remove(&object_key) {
+            self.written.extend(members);
+            out.push('(');
+            for item in items {
+                out.push(' ');
+                self.write_object(out, item, depth);
+            }
+            out.push_str(" )");
+            return;
+        }
+        self.written.insert(object_key.clone());
+        match self.index.subjects.get(&object_key).cloned() {
+            Some((_, predicate_objects)) => {
+                out.push_str("[\n");
+                out.push_str(&INDENT.repeat(depth + 1));
+                self.write_predicate_objects(out, &predicate_objects, depth + 1);
+                out.push('\n');
+                out.push_str(&INDENT.repeat(depth));
+                out.push(']');
+            }
+            None => out.push_str("[]"),
+        }
+    }
+
+    fn term(&mut self, term: &Term) -> String {
+        match term {
+            Term::Iri(iri) => self.iri(iri.as_str()),
+            Term::BlankNode(bnode) => self.blank(bnode.id()),
+            Term::Literal(literal) => {
+                let mut s = string_literal(literal.value());
+                if let Some(lang) = literal.lang() {
+                    s.push('@');
+                    s.push_str(lang);
+                } else if let Some(datatype) = literal.datatype() {
+                    s.push_str("^^");
+                    s.push_str(&self.iri(datatype.as_str()));
+                }
+                s
+            }
+        }
+    }
+
+    fn blank(&self, id: &str) -> String {
+        match self.index.well_known_prefix {
+            Some(_) => iri_ref(&self.index.blank_id(id)),
+            None => self.index.blank_id(id),
+        }
+    }
+
+    /// Longest matching namespace wins.
+    fn iri(&mut self, iri: &str) -> String {
+        let prefix = self
+            .prefixes
+            .iter()
+            .filter(|(_, ns)| iri.starts_with(ns) && is_valid_local(&iri[ns.len()..]))
+            .min_by_key(|(_, ns)| Reverse(ns.len()))
+            .copied();
+        match prefix {
+            Some((name, ns)) => {
+                self.used_prefixes.insert(name);
+                format!("{name}:{}", &iri[ns.len()..])
+            }
+            None => iri_ref(iri),
+        }
+    }
+}
+
+/// Conservative subset of PN_PREFIX.
+fn is_valid_prefix(name: &str) -> bool {
+    match name.chars().next() {
+        None => true,
+        Some(first) => {
+            first.is_alphabetic()
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+                && !name.ends_with('.')
+        }
+    }
+}
+
+/// Conservative subset of PN_LOCAL, without escapes.
+fn is_valid_local(local: &str) -> bool {
+    match local.chars().next() {
+        None => true,
+        Some(first) => {
+            (first.is_alphanumeric() || first == '_')
+                && local
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+                && !local.ends_with('.')
+        }
+    }
+}
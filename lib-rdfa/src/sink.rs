@@ -0,0 +1,165 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    io::{self, Write},
+    sync::mpsc::Sender,
+};
+
+use crate::{
+    RdfaGraph,
+    term::{Statement, Term},
+};
+
+/// Receives triples as soon as the processor produces them.
+pub trait TripleSink<'a> {
+    fn triple(&mut self, subject: Term<'a>, predicate: Term<'a>, object: Term<'a>);
+
+    /// A prefix declared by the document, with @prefix or `xmlns:`, e.g to write CURIEs.
+    /// A prefix can be declared again on another element.
+    fn prefix(&mut self, _prefix: Cow<'a, str>, _iri: Cow<'a, str>) {}
+
+    /// The base of the document, resolved against its `<base>` element,
+    /// given before the first triple.
+    fn base(&mut self, _base: Cow<'a, str>) {}
+
+    fn statement(&mut self, statement: Statement<'a>) {
+        let Statement {
+            subject,
+            predicate,
+            object,
+        } = statement;
+        self.triple(subject, predicate, object);
+    }
+}
+
+impl<'a> TripleSink<'a> for Vec<Statement<'a>> {
+    fn triple(&mut self, subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) {
+        self.push(Statement {
+            subject,
+            predicate,
+            object,
+        });
+    }
+}
+
+impl<'a> TripleSink<'a> for HashSet<Statement<'a>> {
+    fn triple(&mut self, subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) {
+        self.insert(Statement {
+            subject,
+            predicate,
+            object,
+        });
+    }
+}
+
+impl<'a> TripleSink<'a> for RdfaGraph<'a> {
+    fn triple(&mut self, subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) {
+        self.statements.triple(subject, predicate, object);
+    }
+
+    /// The first declaration of a prefix wins.
+    fn prefix(&mut self, prefix: Cow<'a, str>, iri: Cow<'a, str>) {
+        self.prefixes.entry(prefix).or_insert(iri);
+    }
+
+    fn base(&mut self, base: Cow<'a, str>) {
+        self.base = base;
+    }
+}
+
+/// Forward owned statements, e.g to another thread.
+/// Triples are dropped once the receiver hung up.
+impl<'a> TripleSink<'a> for Sender<Statement<'static>> {
+    fn triple(&mut self, subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) {
+        let _ = self.send(
+            Statement {
+                subject,
+                predicate,
+                object,
+            }
+            .into_owned(),
+        );
+    }
+}
+
+/// Count triples without keeping them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TripleCounter {
+    pub count: usize,
+}
+
+impl<'a> TripleSink<'a> for TripleCounter {
+    fn triple(&mut self, _: Term<'a>, _: Term<'a>, _: Term<'a>) {
+        self.count += 1;
+    }
+}
+
+/// Write triples as n-triples, one statement per line, or as n-quads in a named graph.
+/// The first io error stops the writer and is returned by `finish`.
+pub struct NTriplesWriter<W: Write> {
+    writer: W,
+    well_known_prefix: Option<String>,
+    quads: bool,
+    graph_name: Option<String>,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> NTriplesWriter<W> {
+    pub fn new(writer: W, well_known_prefix: Option<&str>) -> Self {
+        NTriplesWriter {
+            writer,
+            well_known_prefix: well_known_prefix.map(String::from),
+            quads: false,
+            graph_name: None,
+            error: None,
+        }
+    }
+
+    /// Write quads in the graph named after the base of the document.
+    pub fn quads(mut self) -> Self {
+        self.quads = true;
+        self
+    }
+
+    /// Write quads in the named graph `graph_name` instead of the base of the document.
+    pub fn graph_name(mut self, graph_name: impl Into<String>) -> Self {
+        self.quads = true;
+        self.graph_name = Some(graph_name.into());
+        self
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<'a, W: Write> TripleSink<'a> for NTriplesWriter<W> {
+    fn triple(&mut self, subject: Term<'a>, predicate: Term<'a>, object: Term<'a>) {
+        if self.error.is_some() {
+            return;
+        }
+        let statement = Statement {
+            subject,
+            predicate,
+            object,
+        };
+        let well_known_prefix = self.well_known_prefix.as_deref();
+        let line = match &self.graph_name {
+            Some(graph_name) => statement.as_nquad_string(graph_name, well_known_prefix),
+            None => statement.as_ntriple_string(well_known_prefix),
+        };
+        if let Err(e) = writeln!(self.writer, "{line}") {
+            self.error = Some(e);
+        }
+    }
+
+    fn base(&mut self, base: Cow<'a, str>) {
+        if self.quads && self.graph_name.is_none() {
+            self.graph_name = Some(base.into_owned());
+        }
+    }
+}
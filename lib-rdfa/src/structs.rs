@@ -1,13 +1,18 @@
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
-    fmt::{Display, Formatter},
     sync::Arc,
 };
 
 use regex::Regex;
 
-use crate::constants::{DATETIME_TYPES, NODE_RDF_XSD_STRING};
+use crate::{
+    HostLanguage, RdfaVersion,
+    constants::DATETIME_TYPES,
+    expansion::Vocabularies,
+    initial_context::{InitialContext, W3C_INITIAL_CONTEXT},
+    term::{BlankNode, Iri, Literal, Statement, Term},
+};
 #[macro_export]
 macro_rules! iri {
     ($name:literal) => {
@@ -17,139 +22,98 @@ macro_rules! iri {
 
 #[derive(Debug)]
 pub struct RdfaGraph<'a> {
-    pub well_known_prefix: Option<&'a str>,
+    /// Resolved base of the document, the default graph name of `to_nquads` and `to_trig`.
+    pub base: Cow<'a, str>,
+    pub well_known_prefix: Option<Cow<'a, str>>,
     pub statements: HashSet<Statement<'a>>,
+    /// Prefixes of the options and of the @prefix attributes, used to write CURIEs.
+    pub prefixes: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    /// Vocab of the options, otherwise the first @vocab of the document.
+    pub vocab: Option<Cow<'a, str>>,
+    /// rdfa:Error / rdfa:Warning resources describing what was ignored and why.
+    /// Only collected when `ParserOptions::processor_graph` is set.
+    pub processor_graph: Option<Box<RdfaGraph<'a>>>,
 }
 
 #[derive(Debug, Default, Clone)]
-pub struct Context<'a> {
-    pub base: &'a str,
+pub(crate) struct Context<'a> {
+    /// Absolute, relative bases are resolved against the parent's.
+    pub base: Cow<'a, str>,
     pub well_known_prefix: Option<&'a str>,
-    pub empty_ref_node_substitute: &'a str,
-    pub vocab: Option<&'a str>,
+    pub vocab: Option<Cow<'a, str>>,
     pub lang: Option<&'a str>,
     pub in_rel: Option<Vec<Node<'a>>>,
     pub in_rev: Option<Vec<Node<'a>>>,
     pub in_list: Option<Vec<Node<'a>>>,
     pub current_node: Option<Node<'a>>,
-    pub prefixes: HashMap<&'a str, &'a str>,
+    /// Lower-cased prefixes.
+    pub prefixes: HashMap<Cow<'a, str>, &'a str>,
+    pub processor_graph: bool,
+    pub microdata: bool,
+    pub vocabularies: Option<&'a Vocabularies>,
+    pub initial_context: Option<&'a InitialContext>,
+    pub host_language: HostLanguage,
+    pub version: RdfaVersion,
+}
+
+impl<'a> Context<'a> {
+    /// The context of the options, the W3C one when parsing without options.
+    pub fn initial_context(&self) -> &'a InitialContext {
+        self.initial_context.unwrap_or(&W3C_INITIAL_CONTEXT)
+    }
 }
 
 #[derive(Debug)]
-pub struct DataTypeFromPattern<'a> {
+pub(crate) struct DataTypeFromPattern<'a> {
     pub pattern: &'a str,
     pub datatype: Node<'a>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Literal<'a> {
-    pub datatype: Option<Box<Node<'a>>>,
-    pub value: Cow<'a, str>,
-    pub lang: Option<&'a str>,
-}
-
-#[derive(Debug, Clone, Eq, PartialOrd, Ord, Hash)]
-pub enum Node<'a> {
+/// Node of the processing, turned into a `Term` once a triple is emitted.
+/// A `TermIri` equals the `Iri` of the same value, a `Ref` equals the node it wraps.
+#[derive(Debug, Clone, Eq)]
+pub(crate) enum Node<'a> {
     Iri(Cow<'a, str>),
     TermIri(Cow<'a, str>),
     Literal(Literal<'a>),
     Ref(Arc<Node<'a>>),
     Blank(String),
-    RefBlank(&'a str),
+    RefBlank(Cow<'a, str>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Statement<'a> {
+/// Triple kept back by the processor, e.g the pending items of an inlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NodeStatement<'a> {
     pub subject: Node<'a>,
     pub predicate: Node<'a>,
     pub object: Node<'a>,
 }
 
-impl Statement<'_> {
-    fn as_ntriple_string(&self, well_known_prefix: Option<&str>) -> String {
-        let Statement {
-            subject,
-            predicate,
-            object,
-        } = self;
-        format!(
-            r#"{} {} {}."#,
-            subject.as_ntriple_string(well_known_prefix),
-            predicate.as_ntriple_string(well_known_prefix),
-            object.as_ntriple_string(well_known_prefix)
-        )
+impl<'a> NodeStatement<'a> {
+    pub fn to_statement(&self) -> Statement<'a> {
+        Statement {
+            subject: self.subject.to_term(),
+            predicate: self.predicate.to_term(),
+            object: self.object.to_term(),
+        }
     }
 }
 
-impl Node<'_> {
-    pub fn is_empty(&self) -> bool {
-        match self {
-            Node::Iri(iri) => iri.is_empty(),
-            Node::TermIri(iri) => iri.is_empty(),
-            Node::Literal(l) => {
-                l.value.is_empty()
-                    && l.datatype.as_ref().filter(|li| !li.is_empty()).is_none()
-                    && l.lang.filter(|lan| lan.is_empty()).is_none()
-            }
-            Node::Ref(r) => r.is_empty(),
-            Node::Blank(_) => false,
-            Node::RefBlank(s) => s.is_empty(),
-        }
-    }
-    fn as_ntriple_string(&self, well_known_prefix: Option<&str>) -> String {
+impl<'a> Node<'a> {
+    pub fn to_term(&self) -> Term<'a> {
         match self {
-            Node::Iri(iri) | Node::TermIri(iri) => format!("<{}>", iri),
-            Node::Ref(iri) => iri.as_ntriple_string(well_known_prefix),
-            Node::Literal(Literal {
-                datatype,
-                lang,
-                value,
-            }) => {
-                const DEFAULT_SEPARATOR: &str = r#"""""#;
-                const FALLBACK_SEPARATOR: &str = "'''";
-                let value = value.replace(FALLBACK_SEPARATOR, "\'\'\'");
-                let separator = if value.ends_with("\"") || value.contains(DEFAULT_SEPARATOR) {
-                    FALLBACK_SEPARATOR
-                } else {
-                    DEFAULT_SEPARATOR
-                };
-                let mut s = format!(r#"{separator}{value}{separator}"#);
-
-                if let Some(datatype) = datatype
-                    .as_ref()
-                    .filter(|dt| dt.as_ref() != &*NODE_RDF_XSD_STRING)
-                {
-                    s.push_str(&format!(
-                        r#"^^{}"#,
-                        datatype.as_ntriple_string(well_known_prefix)
-                    ));
-                } else if let Some(lang) = lang {
-                    s.push_str(&format!(r#"@{lang}"#));
-                }
-                s
-            }
-            Node::Blank(id) => {
-                if let Some(well_known_prefix) = well_known_prefix {
-                    format!("<{well_known_prefix}{id}>",)
-                } else {
-                    format!("_:{id}")
-                }
-            }
-            Node::RefBlank(id) => {
-                if let Some(well_known_prefix) = well_known_prefix {
-                    format!("<{well_known_prefix}{id}>",)
-                } else {
-                    format!("_:{id}")
-                }
-            }
+            Node::Iri(iri) | Node::TermIri(iri) => Term::Iri(Iri::new(iri.clone())),
+            Node::Literal(literal) => Term::Literal(literal.clone()),
+            Node::Ref(r) => r.to_term(),
+            Node::Blank(id) => Term::BlankNode(BlankNode::new(id.clone())),
+            Node::RefBlank(id) => Term::BlankNode(BlankNode::new(id.clone())),
         }
     }
-
-    pub fn is_blank(&self) -> bool {
-        match self {
-            Node::Iri(_) | Node::TermIri(_) | Node::Literal(_) => false,
-            Node::Ref(r) => r.is_blank(),
-            Node::RefBlank(_) | Node::Blank(_) => true,
+    /// Datatype of a literal, blank nodes are not allowed.
+    pub fn to_iri(&self) -> Option<Iri<'a>> {
+        match self.to_term() {
+            Term::Iri(iri) => Some(iri),
+            _ => None,
         }
     }
 }
@@ -172,21 +136,34 @@ impl PartialEq for Node<'_> {
     }
 }
 
-impl Display for RdfaGraph<'_> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(
-            &self
+impl RdfaGraph<'_> {
+    /// Detach the graph from the html document and the processor options,
+    /// e.g to cache it or send it to another thread.
+    pub fn into_owned(self) -> RdfaGraph<'static> {
+        RdfaGraph {
+            base: Cow::Owned(self.base.into_owned()),
+            well_known_prefix: self
+                .well_known_prefix
+                .map(|wkp| Cow::Owned(wkp.into_owned())),
+            statements: self
                 .statements
-                .iter()
-                .map(|s| s.as_ntriple_string(self.well_known_prefix))
-                .collect::<Vec<_>>()
-                .join("\n"),
-        )
+                .into_iter()
+                .map(Statement::into_owned)
+                .collect(),
+            prefixes: self
+                .prefixes
+                .into_iter()
+                .map(|(k, v)| (Cow::Owned(k.into_owned()), Cow::Owned(v.into_owned())))
+                .collect(),
+            vocab: self.vocab.map(|v| Cow::Owned(v.into_owned())),
+            processor_graph: self.processor_graph.map(|pg| Box::new(pg.into_owned())),
+        }
     }
 }
+
 #[allow(unused)]
 impl<'a> DataTypeFromPattern<'a> {
-    pub fn test(&self, value: &'a str) -> Option<Node<'a>> {
+    pub fn test(&self, value: &str) -> Option<Node<'a>> {
         let re = Regex::new(self.pattern).ok()?;
         if re.find(value).filter(|r| r.len() == value.len()).is_some() {
             Some(self.datatype.clone())
@@ -194,7 +171,7 @@ impl<'a> DataTypeFromPattern<'a> {
             None
         }
     }
-    pub fn date_time_from_pattern(value: &'a str) -> Option<Node<'a>> {
+    pub fn date_time_from_pattern(value: &str) -> Option<Node<'a>> {
         for dtp in DATETIME_TYPES {
             if let v @ Some(_) = dtp.test(value) {
                 return v;
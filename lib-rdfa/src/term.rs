@@ -0,0 +1,226 @@
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+};
+
+use crate::{constants::RDF_XSD_STRING, serializer::ntriples::iri_ref};
+
+/// An absolute IRI, written `<iri>`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Iri<'a>(Cow<'a, str>);
+
+/// A blank node, written `_:id`. Ids are only meaningful within one graph.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlankNode<'a>(Cow<'a, str>);
+
+/// A simple, language-tagged or typed literal.
+/// Literals typed xsd:string are simple literals.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Literal<'a> {
+    value: Cow<'a, str>,
+    datatype: Option<Iri<'a>>,
+    lang: Option<Cow<'a, str>>,
+}
+
+/// Subject, predicate or object of a statement.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Term<'a> {
+    Iri(Iri<'a>),
+    BlankNode(BlankNode<'a>),
+    Literal(Literal<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Statement<'a> {
+    pub subject: Term<'a>,
+    pub predicate: Term<'a>,
+    pub object: Term<'a>,
+}
+
+impl<'a> Iri<'a> {
+    pub fn new(iri: impl Into<Cow<'a, str>>) -> Self {
+        Iri(iri.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_owned(self) -> Iri<'static> {
+        Iri(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl<'a> BlankNode<'a> {
+    pub fn new(id: impl Into<Cow<'a, str>>) -> Self {
+        BlankNode(id.into())
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_owned(self) -> BlankNode<'static> {
+        BlankNode(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl<'a> Literal<'a> {
+    pub fn new(value: impl Into<Cow<'a, str>>) -> Self {
+        Literal {
+            value: value.into(),
+            datatype: None,
+            lang: None,
+        }
+    }
+
+    pub fn typed(value: impl Into<Cow<'a, str>>, datatype: Iri<'a>) -> Self {
+        Literal {
+            value: value.into(),
+            datatype: Some(datatype).filter(|dt| dt.as_str() != RDF_XSD_STRING),
+            lang: None,
+        }
+    }
+
+    /// An empty language tag makes a simple literal.
+    pub fn lang_tagged(value: impl Into<Cow<'a, str>>, lang: impl Into<Cow<'a, str>>) -> Self {
+        Literal {
+            value: value.into(),
+            datatype: None,
+            lang: Some(lang.into()).filter(|lang| !lang.is_empty()),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// None for simple and language-tagged literals.
+    pub fn datatype(&self) -> Option<&Iri<'a>> {
+        self.datatype.as_ref()
+    }
+
+    pub fn lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    pub fn into_owned(self) -> Literal<'static> {
+        Literal {
+            value: Cow::Owned(self.value.into_owned()),
+            datatype: self.datatype.map(Iri::into_owned),
+            lang: self.lang.map(|l| Cow::Owned(l.into_owned())),
+        }
+    }
+}
+
+impl<'a> Term<'a> {
+    pub fn as_iri(&self) -> Option<&Iri<'a>> {
+        match self {
+            Term::Iri(iri) => Some(iri),
+            _ => None,
+        }
+    }
+
+    pub fn as_blank_node(&self) -> Option<&BlankNode<'a>> {
+        match self {
+            Term::BlankNode(bnode) => Some(bnode),
+            _ => None,
+        }
+    }
+
+    pub fn as_literal(&self) -> Option<&Literal<'a>> {
+        match self {
+            Term::Literal(literal) => Some(literal),
+            _ => None,
+        }
+    }
+
+    pub fn is_blank_node(&self) -> bool {
+        matches!(self, Term::BlankNode(_))
+    }
+
+    pub fn into_owned(self) -> Term<'static> {
+        match self {
+            Term::Iri(iri) => Term::Iri(iri.into_owned()),
+            Term::BlankNode(bnode) => Term::BlankNode(bnode.into_owned()),
+            Term::Literal(literal) => Term::Literal(literal.into_owned()),
+        }
+    }
+
+    /// Whether the term is the IRI `iri`.
+    pub(crate) fn is_iri(&self, iri: &str) -> bool {
+        self.as_iri().filter(|i| i.as_str() == iri).is_some()
+    }
+}
+
+impl<'a> Statement<'a> {
+    pub fn new(
+        subject: impl Into<Term<'a>>,
+        predicate: impl Into<Term<'a>>,
+        object: impl Into<Term<'a>>,
+    ) -> Self {
+        Statement {
+            subject: subject.into(),
+            predicate: predicate.into(),
+            object: object.into(),
+        }
+    }
+
+    pub fn into_owned(self) -> Statement<'static> {
+        Statement {
+            subject: self.subject.into_owned(),
+            predicate: self.predicate.into_owned(),
+            object: self.object.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<Iri<'a>> for Term<'a> {
+    fn from(iri: Iri<'a>) -> Self {
+        Term::Iri(iri)
+    }
+}
+
+impl<'a> From<BlankNode<'a>> for Term<'a> {
+    fn from(bnode: BlankNode<'a>) -> Self {
+        Term::BlankNode(bnode)
+    }
+}
+
+impl<'a> From<Literal<'a>> for Term<'a> {
+    fn from(literal: Literal<'a>) -> Self {
+        Term::Literal(literal)
+    }
+}
+
+/// N-Triples form, see `iri_ref`.
+impl Display for Iri<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&iri_ref(self.as_str()))
+    }
+}
+
+impl Display for BlankNode<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "_:{}", self.0)
+    }
+}
+
+impl Display for Literal<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.as_ntriple_string())
+    }
+}
+
+impl Display for Term<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.as_ntriple_string(None))
+    }
+}
+
+/// N-Triples form, blank nodes are not skolemized.
+impl Display for Statement<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} .", self.subject, self.predicate, self.object)
+    }
+}
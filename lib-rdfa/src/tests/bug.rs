@@ -1,10 +1,9 @@
 use scraper::Html;
 use tortank::{turtle::turtle_doc::TurtleDoc, utils::DEFAULT_WELL_KNOWN_PREFIX};
 
-use crate::{RdfaGraph, constants::reset_fake_uuid_gen, structs::Context};
+use crate::{RdfaProcessor, SequentialIdGenerator};
 
 #[test]
-#[serial_test::serial]
 pub fn test_host_instead_of_base() {
     let example = r#"
         <!DOCTYPE html>
@@ -16,20 +15,18 @@ pub fn test_host_instead_of_base() {
         </html>
     "#;
     let _ = env_logger::try_init();
-    // reset bnode id generator
-    reset_fake_uuid_gen();
-
     let document = Html::parse_document(example);
     let root = document.root_element();
 
-    let empty_ref_node_substitute = "00000000-0000-0000-0000-000000000000";
-    let root_ctx = Context {
-        base: "https://ranst.meetingburger.net/rmw/09795852-b9a1-4389-b391-d4bac55627a0/agenda",
-        empty_ref_node_substitute,
-        ..Default::default()
-    };
-    let graph = RdfaGraph::parse(&root, root_ctx).unwrap().to_string();
+    let processor = RdfaProcessor::builder()
+        .base("https://ranst.meetingburger.net/rmw/09795852-b9a1-4389-b391-d4bac55627a0/agenda")
+        .empty_ref_node_substitute("00000000-0000-0000-0000-000000000000")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+    let graph = processor.parse(&root).unwrap().to_string();
 
+    // the reference starts with the host of the base, it is not a relative path
     let expected = TurtleDoc::try_from((r#"
         <https://ranst.meetingburger.net/rmw/09795852-b9a1-4389-b391-d4bac55627a0/agenda> <http://www.w3.org/ns/rdfa#usesVocabulary> <http://data.vlaanderen.be/ns/besluit#>.
         <https://ranst.meetingburger.net/rmw/09795852-b9a1-4389-b391-d4bac55627a0#puntbehandelingc9ecabeb-930b-4a4c-8f89-39f79eea98a8> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://data.vlaanderen.be/ns/besluit#BehandelingVanAgendapunt>.
@@ -1,4 +1,3 @@
-use serial_test::serial;
 use test_case::test_case;
 
 use super::cmp_files;
@@ -181,11 +180,10 @@ const INPUT_OUTPUT_DIR: &str = "examples/earl_html5";
 #[test_case("example0332"  ; "Empty @datatype doesn't override inherited @lang, with @content                       : earl_reports_html5_0332 ")]
 #[test_case("example0333"  ; "@content overrides @datetime (with @datatype specified)                               : earl_reports_html5_0333 ")]
 #[test_case("example0334"  ; "@resource changes the current subject for the nested elements                         : earl_reports_html5_0334 ")]
-#[serial]
 fn test(test_name: &str) {
     cmp_files(
         test_name,
         INPUT_OUTPUT_DIR,
-        "http://rdfa.info/test-suite/test-cases/rdfa1.1/html5",
+        "http://rdfa.info/test-suite/test-cases/rdfa1.1/html5/",
     )
 }
@@ -0,0 +1,293 @@
+use crate::{InitialContext, RdfaError, RdfaProcessor};
+
+const HTML: &str = r#"
+    <div about="http://example.org/zitting">
+      <p property="foaf:name besluit:titel">Zitting</p>
+      <a rel="license" href="http://example.org/license">License</a>
+      <a rel="agenda" href="http://example.org/agenda">Agenda</a>
+    </div>
+"#;
+
+fn parse(context: InitialContext) -> String {
+    RdfaProcessor::builder()
+        .base("http://example.org/")
+        .initial_context(context)
+        .build()
+        .unwrap()
+        .parse_str(HTML)
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_w3c_initial_context() {
+    let triples = parse(InitialContext::default());
+    assert!(triples.contains(r#"<http://xmlns.com/foaf/0.1/name> "Zitting""#));
+    assert!(triples.contains("<http://www.w3.org/1999/xhtml/vocab#license>"));
+    assert!(!triples.contains("data.vlaanderen.be"));
+    assert!(!triples.contains("agenda"));
+}
+
+#[test]
+fn test_extend_initial_context() {
+    let triples = parse(
+        InitialContext::w3c()
+            .prefix("Besluit", "http://data.vlaanderen.be/ns/besluit#")
+            .term("agenda", "http://data.vlaanderen.be/ns/besluit#behandelt")
+            .term("license", "http://purl.org/dc/terms/license"),
+    );
+    for triple in [
+        r#"<http://example.org/zitting> <http://xmlns.com/foaf/0.1/name> "Zitting" ."#,
+        r#"<http://example.org/zitting> <http://data.vlaanderen.be/ns/besluit#titel> "Zitting" ."#,
+        "<http://example.org/zitting> <http://data.vlaanderen.be/ns/besluit#behandelt> <http://example.org/agenda> .",
+        "<http://example.org/zitting> <http://purl.org/dc/terms/license> <http://example.org/license> .",
+    ] {
+        assert!(triples.contains(triple), "{triple} not in {triples}");
+    }
+    assert!(!triples.contains("xhtml/vocab#license"));
+}
+
+#[test]
+fn test_empty_initial_context() {
+    let triples = parse(InitialContext::empty());
+    assert!(!triples.contains("http://xmlns.com/foaf/0.1/name"));
+    assert!(!triples.contains("license"));
+    // an undeclared prefix is an IRI, the predicate of a literal
+    assert!(triples.contains(r#"<http://example.org/zitting> <foaf:name> "Zitting" ."#));
+
+    let html = r#"<div prefix="foaf: http://xmlns.com/foaf/0.1/" about="http://example.org/me">
+                    <p property="foaf:name">Me</p></div>"#;
+    let graph = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .initial_context(InitialContext::empty())
+        .build()
+        .unwrap()
+        .parse_str(html)
+        .unwrap();
+    assert_eq!(
+        "<http://example.org/me> <http://xmlns.com/foaf/0.1/name> \"Me\" .\n",
+        graph.to_string()
+    );
+}
+
+#[test]
+fn test_initial_context_from_turtle() {
+    let turtle = r#"
+        @prefix rdfa: <http://www.w3.org/ns/rdfa#> .
+        PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+
+        # prefixes
+        [] rdfa:prefix "besluit" ;
+           rdfa:uri "http://data.vlaanderen.be/ns/besluit#"^^xsd:anyURI .
+        _:foaf a rdfa:PrefixMapping ; rdfa:prefix 'foaf' ; rdfa:uri """http://xmlns.com/foaf/0.1/""" .
+
+        # terms
+        [ rdfa:term "agenda" , "Agenda" ; rdfa:uri "http://data.vlaanderen.be/ns/besluit#behandelt" ] .
+    "#;
+    let context = InitialContext::from_turtle(turtle).unwrap();
+    assert_eq!(
+        InitialContext::empty()
+            .prefix("besluit", "http://data.vlaanderen.be/ns/besluit#")
+            .prefix("foaf", "http://xmlns.com/foaf/0.1/")
+            .term("agenda", "http://data.vlaanderen.be/ns/besluit#behandelt")
+            .term("Agenda", "http://data.vlaanderen.be/ns/besluit#behandelt"),
+        context
+    );
+    assert_eq!(
+        Some("http://data.vlaanderen.be/ns/besluit#behandelt"),
+        context.get_term("AGENDA")
+    );
+    let triples = parse(context);
+    assert!(triples.contains(r#"<http://data.vlaanderen.be/ns/besluit#titel> "Zitting""#));
+    assert!(triples.contains("<http://data.vlaanderen.be/ns/besluit#behandelt>"));
+    assert!(!triples.contains("license"));
+}
+
+#[test]
+fn test_initial_context_from_json_ld() {
+    let expected = InitialContext::empty()
+        .prefix("besluit", "http://data.vlaanderen.be/ns/besluit#")
+        .term("agenda", "http://data.vlaanderen.be/ns/besluit#behandelt");
+    let compacted = r#"{
+        "@context": {"rdfa": "http://www.w3.org/ns/rdfa#", "uri": {"@id": "rdfa:uri", "@type": "@id"}},
+        "@graph": [
+            {"@type": "rdfa:PrefixMapping", "rdfa:prefix": "besluit", "uri": "http://data.vlaanderen.be/ns/besluit#"},
+            {"rdfa:term": {"@value": "agenda"}, "uri": "http://data.vlaanderen.be/ns/besluit#behandelt"}
+        ]
+    }"#;
+    assert_eq!(expected, InitialContext::from_json_ld(compacted).unwrap());
+    let expanded = r#"[
+        {
+            "http://www.w3.org/ns/rdfa#prefix": [{"@value": "besluit"}],
+            "http://www.w3.org/ns/rdfa#uri": [{"@value": "http://data.vlaanderen.be/ns/besluit#"}]
+        },
+        {
+            "http://www.w3.org/ns/rdfa#term": [{"@value": "agenda"}],
+            "http://www.w3.org/ns/rdfa#uri": [{"@id": "http://data.vlaanderen.be/ns/besluit#behandelt"}]
+        }
+    ]"#;
+    assert_eq!(expected, InitialContext::from_json_ld(expanded).unwrap());
+}
+
+#[test]
+fn test_initial_context_in_document_order() {
+    let turtle = r#"
+        @prefix rdfa: <http://www.w3.org/ns/rdfa#> .
+        [] rdfa:prefix "ex" ; rdfa:uri "http://example.org/first#" .
+        [] rdfa:prefix "ex" ; rdfa:uri "http://example.org/second#" .
+        [] rdfa:prefix "ex" ; rdfa:uri "http://example.org/last#" .
+    "#;
+    let context = InitialContext::from_turtle(turtle).unwrap();
+    assert_eq!(Some("http://example.org/last#"), context.get_prefix("ex"));
+
+    let json = r#"[
+        {"http://www.w3.org/ns/rdfa#term": "next", "http://www.w3.org/ns/rdfa#uri": "http://example.org/first"},
+        {"http://www.w3.org/ns/rdfa#term": "next", "http://www.w3.org/ns/rdfa#uri": "http://example.org/last"}
+    ]"#;
+    let context = InitialContext::from_json_ld(json).unwrap();
+    assert_eq!(Some("http://example.org/last"), context.get_term("next"));
+}
+
+#[test]
+fn test_invalid_initial_context() {
+    for turtle in [
+        r#"@prefix rdfa: <http://www.w3.org/ns/rdfa#> . [] rdfa:prefix "ex" ."#,
+        r#"[] <http://www.w3.org/ns/rdfa#prefix> "ex" ; <http://www.w3.org/ns/rdfa#uri> "a", "b" ."#,
+        r#"[] rdfa:prefix "ex" ; rdfa:uri "http://example.org/" ."#,
+        r#"[] <http://www.w3.org/ns/rdfa#prefix> "ex"#,
+    ] {
+        assert!(
+            matches!(
+                InitialContext::from_turtle(turtle),
+                Err(RdfaError::InvalidInitialContext { .. })
+            ),
+            "{turtle} should be invalid"
+        );
+    }
+    for json in [
+        r#"{"http://www.w3.org/ns/rdfa#term": "agenda"}"#,
+        r#"{"http://www.w3.org/ns/rdfa#term": "agenda""#,
+        r#"{"@context": "http://example.org/context.jsonld", "rdfa:term": "agenda"}"#,
+        r#""agenda""#,
+    ] {
+        assert!(
+            matches!(
+                InitialContext::from_json_ld(json),
+                Err(RdfaError::InvalidInitialContext { .. })
+            ),
+            "{json} should be invalid"
+        );
+    }
+    for context in [
+        InitialContext::empty().prefix("2ex", "http://example.org/"),
+        InitialContext::empty().prefix("ex", "not an iri"),
+        InitialContext::empty().term("ex:term", "http://example.org/"),
+    ] {
+        assert!(matches!(
+            RdfaProcessor::builder()
+                .base("http://example.org/")
+                .initial_context(context)
+                .build(),
+            Err(RdfaError::InvalidOption { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_xhtml_vocabulary_terms() {
+    let html = r#"
+        <html>
+          <head>
+            <link rel="Stylesheet" href="style.css" />
+            <link rel="next PREV start" href="chapter2.html" />
+          </head>
+          <body>
+            <a rel="up" href="..">Up</a>
+            <a rel="license copyright" href="license.html">License</a>
+            <a rel="describedby" href="about.html">About</a>
+            <a rel="nofollow" href="other.html">Other</a>
+            <a rel="up:chapter" href="chapter.html">Chapter</a>
+          </body>
+        </html>
+    "#;
+    let parse = |host_language| {
+        RdfaProcessor::builder()
+            .base("http://example.org/book/chapter1.html")
+            .host_language(host_language)
+            .build()
+            .unwrap()
+            .parse_str(html)
+            .unwrap()
+            .to_string()
+    };
+    let triples = parse(crate::HostLanguage::Xhtml1);
+    let base = "<http://example.org/book/chapter1.html>";
+    for (term, object) in [
+        ("stylesheet", "book/style.css"),
+        ("next", "book/chapter2.html"),
+        ("prev", "book/chapter2.html"),
+        ("start", "book/chapter2.html"),
+        ("up", ""),
+        ("license", "book/license.html"),
+        ("copyright", "book/license.html"),
+    ] {
+        let triple = format!(
+            "{base} <http://www.w3.org/1999/xhtml/vocab#{term}> <http://example.org/{object}> ."
+        );
+        assert!(triples.contains(&triple), "{triple} not in {triples}");
+    }
+    assert!(triples.contains(
+        "<http://www.w3.org/2007/05/powder-s#describedby> <http://example.org/book/about.html>"
+    ));
+    assert!(triples.contains("<up:chapter> <http://example.org/book/chapter.html>"));
+    assert!(!triples.contains("nofollow"));
+    assert!(!triples.contains("other.html"));
+
+    // HTML+RDFa only has the terms of the RDFa Core initial context
+    let triples = parse(crate::HostLanguage::Html5);
+    assert!(triples.contains("<http://www.w3.org/1999/xhtml/vocab#license>"));
+    assert!(triples.contains("<http://www.w3.org/2007/05/powder-s#describedby>"));
+    for term in ["stylesheet", "next", "prev", "start", "up", "copyright"] {
+        assert!(!triples.contains(&format!("vocab#{term}>")), "{triples}");
+    }
+}
+
+#[test]
+fn test_rel_terms_ignored_with_property() {
+    let html = r#"
+        <div about="http://example.org/me">
+          <a rel="me" property="foaf:homepage" href="http://example.org/">Home</a>
+          <a rel="next foaf:knows" property="foaf:name" href="http://example.org/you">You</a>
+          <a rel="next" href="http://example.org/next">Next</a>
+        </div>
+    "#;
+    let parse = |host_language| {
+        RdfaProcessor::builder()
+            .base("http://example.org/")
+            .host_language(host_language)
+            .build()
+            .unwrap()
+            .parse_str(html)
+            .unwrap()
+            .to_string()
+    };
+    let triples = parse(crate::HostLanguage::Html5);
+    for triple in [
+        "<http://example.org/me> <http://xmlns.com/foaf/0.1/homepage> <http://example.org/> .",
+        "<http://example.org/me> <http://xmlns.com/foaf/0.1/knows> <http://example.org/you> .",
+        r#"<http://example.org/me> <http://xmlns.com/foaf/0.1/name> "You" ."#,
+    ] {
+        assert!(triples.contains(triple), "{triple} not in {triples}");
+    }
+    // `next` is not a term of HTML+RDFa either
+    assert_eq!(3, triples.lines().count(), "{triples}");
+
+    // XHTML+RDFa keeps the terms of @rel, the XHTML vocabulary is in its initial context
+    let triples = parse(crate::HostLanguage::Xhtml1);
+    assert!(triples.contains(
+        "<http://example.org/me> <http://www.w3.org/1999/xhtml/vocab#next> <http://example.org/next> ."
+    ));
+    assert!(triples.contains(
+        "<http://example.org/me> <http://www.w3.org/1999/xhtml/vocab#next> <http://example.org/you> ."
+    ));
+}
@@ -0,0 +1,106 @@
+use test_case::test_case;
+
+use crate::{RdfaProcessor, iri::resolve};
+
+const RFC_BASE: &str = "http://a/b/c/d;p?q";
+
+// RFC 3986 §5.4.1, normal examples
+#[test_case("g:h"           , "g:h"                  ; "rfc3986_normal_scheme")]
+#[test_case("g"             , "http://a/b/c/g"       ; "rfc3986_normal_g")]
+#[test_case("./g"           , "http://a/b/c/g"       ; "rfc3986_normal_dot_g")]
+#[test_case("g/"            , "http://a/b/c/g/"      ; "rfc3986_normal_g_slash")]
+#[test_case("/g"            , "http://a/g"           ; "rfc3986_normal_absolute_path")]
+#[test_case("//g"           , "http://g"             ; "rfc3986_normal_network_path")]
+#[test_case("?y"            , "http://a/b/c/d;p?y"   ; "rfc3986_normal_query")]
+#[test_case("g?y"           , "http://a/b/c/g?y"     ; "rfc3986_normal_g_query")]
+#[test_case("#s"            , "http://a/b/c/d;p?q#s" ; "rfc3986_normal_fragment")]
+#[test_case("g#s"           , "http://a/b/c/g#s"     ; "rfc3986_normal_g_fragment")]
+#[test_case("g?y#s"         , "http://a/b/c/g?y#s"   ; "rfc3986_normal_g_query_fragment")]
+#[test_case(";x"            , "http://a/b/c/;x"      ; "rfc3986_normal_params")]
+#[test_case("g;x"           , "http://a/b/c/g;x"     ; "rfc3986_normal_g_params")]
+#[test_case("g;x?y#s"       , "http://a/b/c/g;x?y#s" ; "rfc3986_normal_g_params_query_fragment")]
+#[test_case(""              , "http://a/b/c/d;p?q"   ; "rfc3986_normal_empty")]
+#[test_case("."             , "http://a/b/c/"        ; "rfc3986_normal_dot")]
+#[test_case("./"            , "http://a/b/c/"        ; "rfc3986_normal_dot_slash")]
+#[test_case(".."            , "http://a/b/"          ; "rfc3986_normal_dot_dot")]
+#[test_case("../"           , "http://a/b/"          ; "rfc3986_normal_dot_dot_slash")]
+#[test_case("../g"          , "http://a/b/g"         ; "rfc3986_normal_dot_dot_g")]
+#[test_case("../.."         , "http://a/"            ; "rfc3986_normal_dot_dot_twice")]
+#[test_case("../../"        , "http://a/"            ; "rfc3986_normal_dot_dot_slash_twice")]
+#[test_case("../../g"       , "http://a/g"           ; "rfc3986_normal_dot_dot_twice_g")]
+// §5.4.2, abnormal examples
+#[test_case("../../../g"    , "http://a/g"           ; "rfc3986_abnormal_above_root")]
+#[test_case("../../../../g" , "http://a/g"           ; "rfc3986_abnormal_far_above_root")]
+#[test_case("/./g"          , "http://a/g"           ; "rfc3986_abnormal_absolute_dot")]
+#[test_case("/../g"         , "http://a/g"           ; "rfc3986_abnormal_absolute_dot_dot")]
+#[test_case("g."            , "http://a/b/c/g."      ; "rfc3986_abnormal_trailing_dot")]
+#[test_case(".g"            , "http://a/b/c/.g"      ; "rfc3986_abnormal_leading_dot")]
+#[test_case("g.."           , "http://a/b/c/g.."     ; "rfc3986_abnormal_trailing_dot_dot")]
+#[test_case("..g"           , "http://a/b/c/..g"     ; "rfc3986_abnormal_leading_dot_dot")]
+#[test_case("./../g"        , "http://a/b/g"         ; "rfc3986_abnormal_dot_then_dot_dot")]
+#[test_case("./g/."         , "http://a/b/c/g/"      ; "rfc3986_abnormal_trailing_segment_dot")]
+#[test_case("g/./h"         , "http://a/b/c/g/h"     ; "rfc3986_abnormal_inner_dot")]
+#[test_case("g/../h"        , "http://a/b/c/h"       ; "rfc3986_abnormal_inner_dot_dot")]
+#[test_case("g;x=1/./y"     , "http://a/b/c/g;x=1/y" ; "rfc3986_abnormal_params_dot")]
+#[test_case("g;x=1/../y"    , "http://a/b/c/y"       ; "rfc3986_abnormal_params_dot_dot")]
+#[test_case("g?y/./x"       , "http://a/b/c/g?y/./x" ; "rfc3986_abnormal_dot_in_query")]
+#[test_case("g?y/../x"      , "http://a/b/c/g?y/../x"; "rfc3986_abnormal_dot_dot_in_query")]
+#[test_case("g#s/./x"       , "http://a/b/c/g#s/./x" ; "rfc3986_abnormal_dot_in_fragment")]
+#[test_case("g#s/../x"      , "http://a/b/c/g#s/../x"; "rfc3986_abnormal_dot_dot_in_fragment")]
+#[test_case("http:g"        , "http:g"               ; "rfc3986_abnormal_strict_scheme")]
+fn test_rfc3986(reference: &str, expected: &str) {
+    assert_eq!(expected, resolve(RFC_BASE, reference));
+}
+
+#[test_case("http://example.org"          , "#me"         , "http://example.org#me"          ; "no_path_fragment")]
+#[test_case("http://example.org"          , "me"          , "http://example.org/me"          ; "no_path_segment")]
+#[test_case("http://example.org"          , ""            , "http://example.org"             ; "no_path_empty")]
+#[test_case("http://example.org/rmw/agenda", "punt"       , "http://example.org/rmw/punt"    ; "file_name_base")]
+#[test_case("http://example.org/rmw/agenda", "#punt"      , "http://example.org/rmw/agenda#punt" ; "file_name_base_fragment")]
+#[test_case("http://example.org/rmw/agenda", "/zittingen" , "http://example.org/zittingen"   ; "file_name_base_absolute_path")]
+#[test_case("http://example.org/rmw/"     , "?month=01"   , "http://example.org/rmw/?month=01" ; "directory_base_query")]
+#[test_case("http://example.org/a#frag"   , "b"           , "http://example.org/b"           ; "base_fragment_dropped")]
+#[test_case("http://example.org/a?q#frag" , ""            , "http://example.org/a?q"         ; "empty_reference_keeps_query")]
+#[test_case("https://example.org/a/"      , "//cdn.example.org/x.png", "https://cdn.example.org/x.png" ; "network_path_keeps_scheme")]
+#[test_case("http://example.org/été/"     , "café#menu"   , "http://example.org/été/café#menu" ; "iri_characters_kept")]
+#[test_case("http://example.org/a/b"      , "c:d"         , "c:d"                            ; "absolute_reference")]
+#[test_case("http://example.org/a/b"      , "c/d:e"       , "http://example.org/a/c/d:e"     ; "colon_in_relative_path")]
+#[test_case("urn:isbn:0451450523"         , "#chapter"    , "urn:isbn:0451450523#chapter"    ; "non_hierarchical_base")]
+fn test_tricky_references(base: &str, reference: &str, expected: &str) {
+    assert_eq!(expected, resolve(base, reference));
+}
+
+#[test]
+fn test_relative_references_in_rdfa() {
+    let html = r#"
+        <html>
+          <head><base href="../docs/index.html#top"></head>
+          <body vocab="../vocab#">
+            <div about="">
+              <a property="up" href="..">Up</a>
+              <a property="search" href="?q=rdfa">Search</a>
+              <a property="cdn" href="//cdn.example.org/logo.png">Logo</a>
+              <span property="site" resource="/">Site</span>
+              <img property="photo" src="img/me.jpg" />
+            </div>
+          </body>
+        </html>
+    "#;
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/site/pages/agenda")
+        .build()
+        .unwrap();
+    let graph = processor.parse_str(html).unwrap();
+    assert_eq!("http://example.org/site/docs/index.html", graph.base);
+    let triples = graph.to_string();
+    for triple in [
+        "<http://example.org/site/docs/index.html> <http://www.w3.org/ns/rdfa#usesVocabulary> <http://example.org/site/vocab#> .",
+        "<http://example.org/site/docs/index.html> <http://example.org/site/vocab#up> <http://example.org/site/> .",
+        "<http://example.org/site/docs/index.html> <http://example.org/site/vocab#search> <http://example.org/site/docs/index.html?q=rdfa> .",
+        "<http://example.org/site/docs/index.html> <http://example.org/site/vocab#cdn> <http://cdn.example.org/logo.png> .",
+        "<http://example.org/site/docs/index.html> <http://example.org/site/vocab#site> <http://example.org/> .",
+        "<http://example.org/site/docs/index.html> <http://example.org/site/vocab#photo> <http://example.org/site/docs/img/me.jpg> .",
+    ] {
+        assert!(triples.contains(triple), "{triple} not in {triples}");
+    }
+}
@@ -0,0 +1,229 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{BlankNode, Iri, Literal, RdfaGraph, RdfaProcessor, Statement, Term};
+
+fn parse(html: &str) -> RdfaGraph<'static> {
+    RdfaProcessor::builder()
+        .base("http://example.org/people/")
+        .microdata(true)
+        .build()
+        .unwrap()
+        .parse_str(html)
+        .unwrap()
+        .into_owned()
+}
+
+/// A graph of N-Triples, one per line, blank nodes are compared up to isomorphism.
+fn graph(triples: &str) -> RdfaGraph<'_> {
+    RdfaGraph {
+        base: Cow::Borrowed(""),
+        well_known_prefix: None,
+        statements: triples.lines().filter_map(statement).collect(),
+        prefixes: HashMap::new(),
+        vocab: None,
+        processor_graph: None,
+    }
+}
+
+fn statement(triple: &str) -> Option<Statement<'_>> {
+    let triple = triple.trim().strip_suffix(" .")?;
+    let (subject, rest) = triple.split_once(' ')?;
+    let (predicate, object) = rest.split_once(' ')?;
+    Some(Statement::new(term(subject), term(predicate), term(object)))
+}
+
+fn term(term: &str) -> Term<'_> {
+    if let Some(id) = term.strip_prefix("_:") {
+        return BlankNode::new(id).into();
+    }
+    if let Some(iri) = term.strip_prefix('<') {
+        return Iri::new(iri.trim_end_matches('>')).into();
+    }
+    let (value, suffix) = term[1..].rsplit_once('"').unwrap();
+    if let Some(lang) = suffix.strip_prefix('@') {
+        Literal::lang_tagged(value, lang).into()
+    } else if let Some(datatype) = suffix.strip_prefix("^^<") {
+        Literal::typed(value, Iri::new(datatype.trim_end_matches('>'))).into()
+    } else {
+        Literal::new(value).into()
+    }
+}
+
+fn assert_graph(expected: &str, graph: &RdfaGraph) {
+    let expected = self::graph(expected);
+    let diff = expected.diff(graph);
+    assert!(diff.is_empty(), "{diff:#?}\n{graph}");
+}
+
+#[test]
+fn test_microdata_item() {
+    let html = r#"
+        <div itemscope itemtype="http://schema.org/Person" itemid="amanda" lang="en">
+          <span itemprop="name">Amanda</span>
+          <img itemprop="image" src="amanda.jpg" alt="">
+          <a itemprop="url sameAs" href="https://amanda.example.com/">Home</a>
+          <meta itemprop="email" content="amanda@example.org">
+          <time itemprop="birthDate" datetime="1990-04-02">2 April</time>
+          <time itemprop="description">someday</time>
+          <data itemprop="height" value="172">1m72</data>
+          <meter itemprop="weight" value="61.5" min="0" max="200">61.5kg</meter>
+          <data itemprop="gender" value="F">Female</data>
+          <div itemprop="address" itemscope>
+            <span itemprop="addressLocality" lang="nl">Gent</span>
+          </div>
+          <span itemprop="http://xmlns.com/foaf/0.1/nick">Mandy</span>
+        </div>
+    "#;
+    assert_graph(
+        r#"
+        <http://example.org/people/amanda> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> .
+        <http://example.org/people/amanda> <http://schema.org/name> "Amanda"@en .
+        <http://example.org/people/amanda> <http://schema.org/image> <http://example.org/people/amanda.jpg> .
+        <http://example.org/people/amanda> <http://schema.org/url> <https://amanda.example.com/> .
+        <http://example.org/people/amanda> <http://schema.org/sameAs> <https://amanda.example.com/> .
+        <http://example.org/people/amanda> <http://schema.org/email> "amanda@example.org"@en .
+        <http://example.org/people/amanda> <http://schema.org/birthDate> "1990-04-02"^^<http://www.w3.org/2001/XMLSchema#date> .
+        <http://example.org/people/amanda> <http://schema.org/description> "someday" .
+        <http://example.org/people/amanda> <http://schema.org/height> "172"^^<http://www.w3.org/2001/XMLSchema#integer> .
+        <http://example.org/people/amanda> <http://schema.org/weight> "61.5"^^<http://www.w3.org/2001/XMLSchema#double> .
+        <http://example.org/people/amanda> <http://schema.org/gender> "F"@en .
+        <http://example.org/people/amanda> <http://schema.org/address> _:address .
+        _:address <http://schema.org/addressLocality> "Gent"@nl .
+        <http://example.org/people/amanda> <http://xmlns.com/foaf/0.1/nick> "Mandy"@en .
+        <http://example.org/people/> <http://www.w3.org/ns/md#item> _:items .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> <http://example.org/people/amanda> .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .
+        "#,
+        &parse(html),
+    );
+}
+
+#[test]
+fn test_microdata_itemref() {
+    let html = r#"
+        <html><body>
+          <div itemscope itemtype="https://schema.org/Event" itemref="location organizer missing">
+            <span itemprop="name">Gemeenteraad</span>
+          </div>
+          <p id="location" itemprop="location">Stadhuis</p>
+          <div id="organizer" itemprop="organizer" itemscope itemtype="https://schema.org/Organization"
+               itemref="organizer">
+            <span itemprop="name">Gemeente</span>
+          </div>
+          <div itemscope itemtype="https://schema.org/Event" itemref="location">
+            <span itemprop="name">Commissie</span>
+          </div>
+        </body></html>
+    "#;
+    // the organizer is the value of a property, not a top-level item
+    assert_graph(
+        r#"
+        _:council <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/Event> .
+        _:council <https://schema.org/name> "Gemeenteraad" .
+        _:council <https://schema.org/location> "Stadhuis" .
+        _:council <https://schema.org/organizer> _:city .
+        _:city <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/Organization> .
+        _:city <https://schema.org/name> "Gemeente" .
+        _:committee <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://schema.org/Event> .
+        _:committee <https://schema.org/name> "Commissie" .
+        _:committee <https://schema.org/location> "Stadhuis" .
+        <http://example.org/people/> <http://www.w3.org/ns/md#item> _:items .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:council .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:items2 .
+        _:items2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:committee .
+        _:items2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .
+        "#,
+        &parse(html),
+    );
+}
+
+#[test]
+fn test_microdata_vocabulary_rules() {
+    let html = r#"
+        <div itemscope itemtype="http://schema.org/Book">
+          <link itemprop="additionalType" href="http://schema.org/Product">
+          <span itemprop="additionalType">Paperback</span>
+          <span itemprop="name">Dune</span>
+        </div>
+        <div itemscope itemtype="http://n.whatwg.org/work">
+          <span itemprop="author">Frank</span>
+          <span itemprop="author">Brian</span>
+        </div>
+        <div itemscope itemtype="http://example.org/vocab#Thing">
+          <span itemprop="label">Thing</span>
+        </div>
+        <div itemscope itemtype="http://microformats.org/profile/hcard">
+          <span itemprop="fn">Amanda</span>
+        </div>
+        <div itemscope>
+          <span itemprop="name">No vocabulary</span>
+          <span itemprop="http://purl.org/dc/terms/title">Absolute</span>
+        </div>
+    "#;
+    // additionalType is a sub-property of rdf:type for its IRIs only,
+    // the values of the work vocabulary are lists
+    // and the properties of an item without vocabulary are only kept when absolute
+    assert_graph(
+        r#"
+        _:book <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Book> .
+        _:book <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Product> .
+        _:book <http://schema.org/additionalType> <http://schema.org/Product> .
+        _:book <http://schema.org/additionalType> "Paperback" .
+        _:book <http://schema.org/name> "Dune" .
+        _:work <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://n.whatwg.org/work> .
+        _:work <http://n.whatwg.org/work#author> _:authors .
+        _:authors <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> "Frank" .
+        _:authors <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:authors2 .
+        _:authors2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> "Brian" .
+        _:authors2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .
+        _:thing <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/vocab#Thing> .
+        _:thing <http://example.org/vocab#label> "Thing" .
+        _:card <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://microformats.org/profile/hcard> .
+        _:card <http://microformats.org/profile/hcard#fn> "Amanda" .
+        _:untyped <http://purl.org/dc/terms/title> "Absolute" .
+        <http://example.org/people/> <http://www.w3.org/ns/md#item> _:items .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:book .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:items2 .
+        _:items2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:work .
+        _:items2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:items3 .
+        _:items3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:thing .
+        _:items3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:items4 .
+        _:items4 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:card .
+        _:items4 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:items5 .
+        _:items5 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:untyped .
+        _:items5 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .
+        "#,
+        &parse(html),
+    );
+}
+
+#[test]
+fn test_microdata_disabled_by_default() {
+    let html = r#"
+        <div itemscope itemtype="http://schema.org/Person" vocab="http://schema.org/" typeof="Person">
+          <span itemprop="name" property="name">Amanda</span>
+        </div>
+    "#;
+    let rdfa = r#"
+        <http://example.org/people/> <http://www.w3.org/ns/rdfa#usesVocabulary> <http://schema.org/> .
+        _:rdfa <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> .
+        _:rdfa <http://schema.org/name> "Amanda" .
+    "#;
+    let graph = RdfaProcessor::builder()
+        .base("http://example.org/people/")
+        .build()
+        .unwrap()
+        .parse_str(html)
+        .unwrap();
+    assert_graph(rdfa, &graph);
+
+    // microdata and RDFa in the same graph
+    let microdata = r#"
+        _:item <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> .
+        _:item <http://schema.org/name> "Amanda" .
+        <http://example.org/people/> <http://www.w3.org/ns/md#item> _:items .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> _:item .
+        _:items <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .
+    "#;
+    assert_graph(&format!("{rdfa}{microdata}"), &parse(html));
+}
@@ -3,10 +3,13 @@ use std::{ops::Add, path::PathBuf};
 use scraper::Html;
 use tortank::turtle::turtle_doc::TurtleDoc;
 
-use crate::{Context, RdfaGraph, constants::reset_fake_uuid_gen};
+use crate::{RdfaProcessor, SequentialIdGenerator};
 
 mod bug;
 mod earl_html5;
+mod initial_context;
+mod iri;
+mod microdata;
 mod other;
 mod rdfa_core;
 mod rdfa_primer;
@@ -19,8 +22,6 @@ fn cmp_files(test_name: &str, input_output_dir: &str, base: &str) {
     let _ = env_logger::try_init();
 
     println!("running test {test_name}");
-    // reset bnode id generator
-    reset_fake_uuid_gen();
 
     let path_buf = PathBuf::from(input_output_dir);
     let path_to_html = path_buf.join(format!("{test_name}.html"));
@@ -45,13 +46,13 @@ fn cmp_files(test_name: &str, input_output_dir: &str, base: &str) {
     let document = Html::parse_document(html);
     let root = document.root_element();
 
-    let empty_ref_node_substitute = "00000000-0000-0000-0000-000000000000";
-    let root_ctx = Context {
-        base,
-        empty_ref_node_substitute,
-        ..Default::default()
-    };
-    let graph = RdfaGraph::parse(&root, root_ctx).unwrap().to_string();
+    let processor = RdfaProcessor::builder()
+        .base(base)
+        .empty_ref_node_substitute("00000000-0000-0000-0000-000000000000")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+    let graph = processor.parse(&root).unwrap().to_string();
 
     if WRITE_RESULT_TO_FILE {
         std::fs::write("/tmp/res.ttl", &graph).expect("could not write file");
@@ -1,6 +1,7 @@
-use serial_test::serial;
 use test_case::test_case;
 
+use crate::HostLanguage;
+
 use super::cmp_files;
 const INPUT_OUTPUT_DIR: &str = "examples/other";
 
@@ -32,7 +33,6 @@ const INPUT_OUTPUT_DIR: &str = "examples/other";
 #[test_case("example0026"  ; "From rdfa.info/play person                                                                   : other_0026 ")]
 #[test_case("example0027"  ; "Missing link to publication                                                                  : other_0027 ")]
 #[test_case("example0028"  ; "Trying our bests to parse the text                                                           : other_0028 ")]
-#[serial]
 fn test(test_name: &str) {
     cmp_files(
         test_name,
@@ -40,3 +40,1552 @@ fn test(test_name: &str) {
         "http://rdfa.info/test-suite/test-cases/rdfa1.1/html5/",
     )
 }
+
+#[test]
+fn test_invalid_options() {
+    use crate::{RdfaError, RdfaProcessor};
+    let err = RdfaProcessor::builder()
+        .base("http://example.org")
+        .empty_ref_node_substitute("")
+        .build()
+        .unwrap_err();
+    assert!(
+        matches!(err, RdfaError::InvalidOption { option, .. } if option == "empty_ref_node_substitute")
+    );
+
+    let err = RdfaProcessor::builder().build().unwrap_err();
+    assert!(matches!(err, RdfaError::InvalidOption { option, .. } if option == "base"));
+
+    let err = RdfaProcessor::builder()
+        .base("http://example.org")
+        .prefix("ex:", "http://example.org/")
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, RdfaError::InvalidOption { option, .. } if option == "prefix"));
+}
+
+#[test]
+fn test_empty_blank_node_per_parse() {
+    use crate::RdfaProcessor;
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap();
+    let html = r#"<p about="[_:]" property="http://xmlns.com/foaf/0.1/name">Dan</p>
+                  <p about="[_:]" property="http://xmlns.com/foaf/0.1/nick">Danbri</p>"#;
+    let subjects = || {
+        let graph = processor.parse_str(html).unwrap();
+        let mut subjects = graph
+            .statements
+            .iter()
+            .map(|stmt| stmt.subject.to_string())
+            .collect::<Vec<_>>();
+        subjects.dedup();
+        subjects
+    };
+    let first = subjects();
+    assert_eq!(1, first.len(), "{first:?}");
+    // parses don't share the blank node
+    assert_ne!(first, subjects());
+}
+
+#[test]
+fn test_options() {
+    use crate::RdfaProcessor;
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .prefix("besluit", "http://data.vlaanderen.be/ns/besluit#")
+        .vocab("http://schema.org/")
+        .lang("nl")
+        .build()
+        .unwrap();
+    let graph = processor
+        .parse_str(r#"<div typeof="besluit:Besluit" about="b1"><p property="name">Titel</p></div>"#)
+        .unwrap()
+        .to_string();
+    let mut triples = graph.lines().collect::<Vec<_>>();
+    triples.sort();
+    assert_eq!(
+        vec![
+            r#"<http://example.org/b1> <http://schema.org/name> "Titel"@nl ."#,
+            "<http://example.org/b1> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://data.vlaanderen.be/ns/besluit#Besluit> .",
+        ],
+        triples
+    );
+}
+
+#[test]
+fn test_error_location() {
+    use crate::{ErrorLocation, RdfaError};
+    let err = RdfaError::UnresolvedCurie {
+        value: "[foo:bar]".into(),
+        location: ErrorLocation::new("http://example.org/"),
+    }
+    .in_attribute("div", "about");
+    assert_eq!(Some("[foo:bar]"), err.value());
+    assert_eq!(
+        "unresolved CURIE '[foo:bar]' in @about of <div> (base: http://example.org/)",
+        err.to_string()
+    );
+}
+
+#[test]
+fn test_processor_graph() {
+    use crate::RdfaProcessor;
+    let html = r#"
+        <div prefix="ex: http://example.org/ ex: http://example.com/ dangling">
+          <p property="name">Amanda</p>
+          <p about="[foo:bar]" property="ex:name">Ivan</p>
+        </div>
+    "#;
+    let document = scraper::Html::parse_document(html);
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org")
+        .processor_graph(true)
+        .build()
+        .unwrap();
+    let graph = processor.parse(&document.root_element()).unwrap();
+    let processor_graph = graph.processor_graph.unwrap().to_string();
+    for class in [
+        "<http://www.w3.org/ns/rdfa#UnresolvedTerm>",
+        "<http://www.w3.org/ns/rdfa#UnresolvedCURIE>",
+        "<http://www.w3.org/ns/rdfa#PrefixRedefinition>",
+    ] {
+        assert!(processor_graph.contains(class), "{class} not reported");
+    }
+    assert!(processor_graph.contains(r#""/html[1]/body[1]/div[1]/p[1]""#));
+    // the dangling declaration is a warning, the document can still be processed
+    assert!(processor_graph.contains("invalid prefix declaration 'dangling'"));
+    assert!(!processor_graph.contains("<http://www.w3.org/ns/rdfa#DocumentError>"));
+    assert!(!processor_graph.contains("<http://www.w3.org/ns/rdfa#Error>"));
+
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org")
+        .build()
+        .unwrap();
+    let graph = processor.parse(&document.root_element()).unwrap();
+    assert!(graph.processor_graph.is_none());
+}
+
+#[test]
+fn test_processor_graph_classes() {
+    use crate::{RdfaError, RdfaProcessor, SequentialIdGenerator, processor_graph::ProcessorGraph};
+    let html = r#"
+        <div prefix="ex: http://example.org/ dangling">
+          <div prefix="ex: http://example.com/ dc: http://purl.org/dc/terms/">
+            <p about="[foo:bar]" property="name">Ivan</p>
+          </div>
+          <p prefix="ex: http://example.org/" property="ex:name">Amanda</p>
+        </div>
+    "#;
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .processor_graph(true)
+        .build()
+        .unwrap();
+    let processor_graph = processor
+        .parse_str(html)
+        .unwrap()
+        .processor_graph
+        .unwrap()
+        .to_string();
+    let types = |class: &str| {
+        let class = format!("<http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <{class}> .");
+        let mut messages = processor_graph
+            .lines()
+            .filter(|line| line.ends_with(&class))
+            .filter_map(|line| line.split_whitespace().next())
+            .collect::<Vec<_>>();
+        messages.sort();
+        messages
+    };
+    let warnings = types("http://www.w3.org/ns/rdfa#Warning");
+    for (class, count) in [
+        ("http://www.w3.org/ns/rdfa#UnresolvedCURIE", 1),
+        ("http://www.w3.org/ns/rdfa#UnresolvedTerm", 1),
+        // redefined on a nested element, not the same IRI declared again nor the initial dc
+        ("http://www.w3.org/ns/rdfa#PrefixRedefinition", 1),
+    ] {
+        let messages = types(class);
+        assert_eq!(count, messages.len(), "{class} in {processor_graph}");
+        assert!(messages.iter().all(|m| warnings.contains(m)));
+    }
+    // the invalid declaration is a plain warning
+    assert_eq!(4, warnings.len(), "{processor_graph}");
+    assert!(types("http://www.w3.org/ns/rdfa#Error").is_empty());
+    assert!(processor_graph.contains("prefix 'ex' redefined in @prefix of <div>"));
+
+    // a document that cannot be processed is an error
+    let mut graph = ProcessorGraph::new(true);
+    let document = scraper::Html::parse_document("<p></p>");
+    graph.report(
+        &RdfaError::InvalidXml {
+            reason: "unexpected end of stream".into(),
+        },
+        &document.root_element(),
+        &mut SequentialIdGenerator::default(),
+    );
+    let processor_graph = graph
+        .into_graph("http://example.org/".into(), None)
+        .unwrap()
+        .to_string();
+    for class in ["DocumentError", "Error"] {
+        assert!(processor_graph.contains(&format!(
+            "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/ns/rdfa#{class}> ."
+        )));
+    }
+}
+
+#[test]
+fn test_owned_graph() {
+    use crate::{RdfaGraph, RdfaProcessor};
+    fn assert_send_sync<T: Send + Sync + 'static>(_: &T) {}
+
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .well_known_prefix("http://example.org/.well-known/genid#")
+        .lang("en")
+        .build()
+        .unwrap();
+    let graph: RdfaGraph<'static> = {
+        let html = String::from(
+            r#"<div vocab="http://schema.org/" typeof="Person"><p property="name">Amanda</p></div>"#,
+        );
+        processor.parse_str(&html).unwrap()
+    };
+    assert_send_sync(&graph);
+    let expected = graph.to_string();
+    let actual = std::thread::spawn(move || graph.to_string())
+        .join()
+        .unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_triple_sinks() {
+    use std::collections::HashSet;
+
+    use crate::{NTriplesWriter, RdfaProcessor, SequentialIdGenerator, Statement, TripleCounter};
+    let html = std::fs::read_to_string("examples/earl_html5/example0321.html").unwrap();
+    let document = scraper::Html::parse_document(&html);
+    let root = document.root_element();
+    let processor = RdfaProcessor::builder()
+        .base("http://rdfa.info/test-suite/test-cases/rdfa1.1/html5/")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+
+    let graph = processor.parse(&root).unwrap();
+
+    let mut writer = NTriplesWriter::new(vec![], None);
+    processor.parse_into(&root, &mut writer).unwrap();
+    let ntriples = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(
+        graph.to_string().lines().collect::<HashSet<_>>(),
+        ntriples.lines().collect::<HashSet<_>>()
+    );
+
+    let mut counter = TripleCounter::default();
+    processor.parse_into(&root, &mut counter).unwrap();
+    assert_eq!(graph.statements.len(), counter.count);
+
+    let (mut tx, rx) = std::sync::mpsc::channel::<Statement<'static>>();
+    processor.parse_into(&root, &mut tx).unwrap();
+    drop(tx);
+    assert_eq!(graph.statements.len(), rx.iter().count());
+}
+
+#[test_case("rdfa: http://www.w3.org/ns/rdfa#", "rdfa:Pattern" ; "rdfa prefix")]
+#[test_case("rdfa: http://www.w3.org/ns/rdfa# p: http://www.w3.org/ns/rdfa#Pat", "p:tern" ; "prefixed alias")]
+fn test_copy_pattern_typed_last(prefix: &str, pattern: &str) {
+    use crate::{NTriplesWriter, RdfaProcessor};
+    let html = format!(
+        r##"
+        <div vocab="http://schema.org/" prefix="{prefix}">
+          <p about="#pattern" property="name">Amanda</p>
+          <div typeof="Person"><link property="rdfa:copy" href="#pattern"/></div>
+          <p about="#pattern" typeof="{pattern}" property="jobTitle">Engineer</p>
+          <p about="#other" property="name">Ivan</p>
+        </div>
+    "##
+    );
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap();
+    let document = scraper::Html::parse_document(&html);
+    let mut writer = NTriplesWriter::new(vec![], None);
+    processor
+        .parse_into(&document.root_element(), &mut writer)
+        .unwrap();
+    let triples = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert!(
+        !triples.contains("<http://example.org/#pattern>"),
+        "{triples}"
+    );
+    for triple in [
+        r#"<http://schema.org/name> "Amanda" ."#,
+        r#"<http://schema.org/jobTitle> "Engineer" ."#,
+        r#"<http://example.org/#other> <http://schema.org/name> "Ivan" ."#,
+    ] {
+        assert!(triples.contains(triple), "{triple} not in {triples}");
+    }
+}
+
+#[test]
+fn test_blank_node_ids() {
+    use crate::{PrefixedIdGenerator, RdfaProcessor};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .blank_node_ids(|| PrefixedIdGenerator::new("b"))
+        .build()
+        .unwrap();
+    let html =
+        r#"<div vocab="http://schema.org/" typeof="Person"><p property="name">Amanda</p></div>"#;
+    let parse = |html| {
+        let graph = processor.parse_str(html).unwrap().to_string();
+        let mut triples = graph.lines().map(String::from).collect::<Vec<_>>();
+        triples.sort();
+        triples
+    };
+    let first = parse(html);
+    assert_eq!(
+        vec![
+            "<http://example.org/> <http://www.w3.org/ns/rdfa#usesVocabulary> <http://schema.org/> .",
+            r#"_:b1 <http://schema.org/name> "Amanda" ."#,
+            "_:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://schema.org/Person> .",
+        ],
+        first
+    );
+    // the counter is not shared between parses
+    assert_eq!(first, parse(html));
+
+    // a generated id never merges with a label of the document
+    let html = r#"<div vocab="http://schema.org/">
+                    <div typeof="Person"><p property="name">Amanda</p></div>
+                    <p about="[_:b1]" property="name">Ivan</p>
+                    <p about="[_:b2]" property="name">Nordine</p>
+                    <div typeof="Person"><p property="name">Dan</p></div>
+                  </div>"#;
+    let triples = parse(html);
+    let subject = |name: &str| {
+        let name = format!(r#"<http://schema.org/name> "{name}" ."#);
+        triples
+            .iter()
+            .find(|t| t.ends_with(&name))
+            .and_then(|t| t.split_whitespace().next())
+            .unwrap()
+            .to_string()
+    };
+    let subjects = ["Amanda", "Ivan", "Nordine", "Dan"].map(subject);
+    // Ivan gets the next id, then Nordine's label is taken by Ivan
+    assert_eq!(["_:b1", "_:b2", "_:b3", "_:b4"], subjects, "{triples:?}");
+}
+
+#[test]
+fn test_turtle() {
+    use crate::{RdfaProcessor, SequentialIdGenerator};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+    let html = r#"
+        <div prefix="ex: http://example.org/ns#" about="me" typeof="foaf:Person">
+          <p property="foaf:name">Amanda</p>
+          <p property="foaf:nick">Mandy</p>
+          <p property="foaf:nick">"Mand\y"</p>
+          <div rel="foaf:knows"><div typeof="foaf:Person"><p property="foaf:name">Ivan</p></div></div>
+          <p property="ex:colors" inlist>red</p>
+          <p property="ex:colors" inlist>blue</p>
+        </div>
+    "#;
+    let turtle = processor.parse_str(html).unwrap().to_turtle();
+    assert_eq!(
+        r#"@prefix ex: <http://example.org/ns#> .
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+
+<http://example.org/me> a foaf:Person ;
+    ex:colors ( "red" "blue" ) ;
+    foaf:knows [
+        a foaf:Person ;
+        foaf:name "Ivan"
+    ] ;
+    foaf:name "Amanda" ;
+    foaf:nick "Mandy", "\"Mand\\y\"" .
+"#,
+        turtle
+    );
+}
+
+#[test_case("examples/earl_html5", "example0088" ; "nested blank nodes")]
+#[test_case("examples/earl_html5", "example0218" ; "inlist")]
+#[test_case("examples/earl_html5", "example0321" ; "rdfa copy")]
+#[test_case("examples/other", "example0005" ; "prefixes")]
+fn test_turtle_roundtrip(input_dir: &str, test_name: &str) {
+    use regex::Regex;
+    use tortank::turtle::turtle_doc::TurtleDoc;
+
+    use crate::{RdfaProcessor, SequentialIdGenerator};
+    // blank node labels are not kept by the turtle parser
+    fn triples(doc: &str) -> Vec<String> {
+        let bnode = Regex::new(r"_:[^\s>]+").unwrap();
+        let doc = TurtleDoc::try_from((doc, None)).unwrap().to_string();
+        let mut triples = doc
+            .lines()
+            .map(|l| bnode.replace_all(l, "_:b").to_string())
+            .collect::<Vec<_>>();
+        triples.sort();
+        triples
+    }
+    let html = std::fs::read_to_string(format!("{input_dir}/{test_name}.html")).unwrap();
+    let processor = RdfaProcessor::builder()
+        .base("http://rdfa.info/test-suite/test-cases/rdfa1.1/html5/")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+    let graph = processor.parse_str(&html).unwrap();
+    let turtle = graph.to_turtle();
+    assert_eq!(triples(&graph.to_string()), triples(&turtle), "{turtle}");
+}
+
+#[test]
+fn test_json_ld() {
+    use serde_json::json;
+
+    use crate::{JsonLdForm, RdfaProcessor, SequentialIdGenerator};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+    let html = r#"
+        <div prefix="ex: http://example.org/ns#" vocab="http://schema.org/" about="me" typeof="Person">
+          <p property="name" lang="en">Amanda</p>
+          <p property="ex:bio" datatype="rdf:HTML">I <em>like</em> graphs</p>
+          <p property="ex:colors" inlist>red</p>
+          <p property="ex:colors" inlist>blue</p>
+        </div>
+    "#;
+    let graph = processor.parse_str(html).unwrap();
+    let me = json!({
+        "@context": {
+            "@vocab": "http://schema.org/",
+            "ex": "http://example.org/ns#"
+        },
+        "@id": "http://example.org/me",
+        "@type": "Person",
+        "ex:bio": {
+            "@type": "http://www.w3.org/1999/02/22-rdf-syntax-ns#HTML",
+            "@value": "I <em>like</em> graphs"
+        },
+        "ex:colors": { "@list": ["red", "blue"] },
+        "name": { "@language": "en", "@value": "Amanda" }
+    });
+    let compacted = graph.to_json_ld(JsonLdForm::Compacted);
+    let mut node = compacted["@graph"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["@id"] == "http://example.org/me")
+        .cloned()
+        .unwrap();
+    node["@context"] = compacted["@context"].clone();
+    assert_eq!(me, node);
+
+    let expanded = graph.to_json_ld(JsonLdForm::Expanded);
+    let me = expanded
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["@id"] == "http://example.org/me")
+        .unwrap();
+    assert_eq!(json!(["http://schema.org/Person"]), me["@type"]);
+    assert_eq!(
+        json!([{ "@list": [{ "@value": "red" }, { "@value": "blue" }] }]),
+        me["http://example.org/ns#colors"]
+    );
+}
+
+#[test]
+fn test_nquads_and_trig() {
+    use crate::{NTriplesWriter, RdfaProcessor, SequentialIdGenerator};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/page")
+        .well_known_prefix("http://example.org/.well-known/genid/")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+    let html = r#"
+        <html>
+          <head><base href="http://example.com/crawled/"></head>
+          <body prefix="foaf: http://xmlns.com/foaf/0.1/">
+            <div about="me" rel="foaf:knows"><p resource="_:ivan" typeof="foaf:Person">Ivan</p></div>
+          </body>
+        </html>
+    "#;
+    let graph = processor.parse_str(html).unwrap();
+    assert_eq!("http://example.com/crawled/", graph.base);
+
+    let mut quads = graph
+        .to_nquads(None)
+        .lines()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    quads.sort();
+    assert_eq!(
+        vec![
+            "<http://example.com/crawled/me> <http://xmlns.com/foaf/0.1/knows> <http://example.org/.well-known/genid/ivan> <http://example.com/crawled/> .",
+            "<http://example.org/.well-known/genid/ivan> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://xmlns.com/foaf/0.1/Person> <http://example.com/crawled/> .",
+        ],
+        quads
+    );
+    assert!(
+        graph
+            .to_nquads(Some("http://example.org/crawl/1"))
+            .lines()
+            .all(|q| q.ends_with(" <http://example.org/crawl/1> ."))
+    );
+
+    assert_eq!(
+        r#"@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+
+<http://example.com/crawled/> {
+    <http://example.com/crawled/me> foaf:knows <http://example.org/.well-known/genid/ivan> .
+
+    <http://example.org/.well-known/genid/ivan> a foaf:Person .
+}
+"#,
+        graph.to_trig(None)
+    );
+
+    let document = scraper::Html::parse_document(html);
+    let mut writer = NTriplesWriter::new(vec![], None).graph_name("http://example.org/crawl/1");
+    processor
+        .parse_into(&document.root_element(), &mut writer)
+        .unwrap();
+    let quads = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(2, quads.lines().count());
+    assert!(
+        quads
+            .lines()
+            .all(|q| q.ends_with(" <http://example.org/crawl/1> ."))
+    );
+
+    // by default, the graph is named after the resolved base of the document
+    let mut writer = NTriplesWriter::new(vec![], None).quads();
+    processor
+        .parse_into(&document.root_element(), &mut writer)
+        .unwrap();
+    let quads = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(2, quads.lines().count());
+    assert!(
+        quads
+            .lines()
+            .all(|q| q.ends_with(" <http://example.com/crawled/> ."))
+    );
+
+    // so is the processor graph
+    let graph = RdfaProcessor::builder()
+        .base("http://example.org/page")
+        .processor_graph(true)
+        .build()
+        .unwrap()
+        .parse_str(r#"<html><head><base href="http://example.com/crawled/"></head><body><p property="nope:x">x</p></body></html>"#)
+        .unwrap();
+    assert_eq!(
+        "http://example.com/crawled/",
+        graph.processor_graph.unwrap().base
+    );
+}
+
+#[test]
+fn test_ntriples_escaping() {
+    use crate::{Iri, Literal, NTriplesWriter, RdfaProcessor, TripleSink};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap();
+    let html =
+        "<p about=\"me\" property=\"http://schema.org/description\">line \"1\"\nline\t2 \\ end</p>";
+    let graph = processor.parse_str(html).unwrap();
+    assert_eq!(
+        "<http://example.org/me> <http://schema.org/description> \"line \\\"1\\\"\\nline\\t2 \\\\ end\" .\n",
+        graph.to_string()
+    );
+
+    // sorted, with lowercase language tags
+    let html = r#"<div about="me"><p property="http://schema.org/name" lang="nl-BE">Amanda</p>
+                  <p property="http://schema.org/alternateName">Mandy</p></div>"#;
+    assert_eq!(
+        r#"<http://example.org/me> <http://schema.org/alternateName> "Mandy" .
+<http://example.org/me> <http://schema.org/name> "Amanda"@nl-be .
+"#,
+        processor.parse_str(html).unwrap().to_string()
+    );
+
+    // characters that are not allowed in an IRI are percent-encoded
+    let mut writer = NTriplesWriter::new(vec![], None);
+    writer.triple(
+        Iri::new("http://example.org/a b").into(),
+        Iri::new("http://schema.org/name").into(),
+        Literal::new("x").into(),
+    );
+    assert_eq!(
+        "<http://example.org/a%20b> <http://schema.org/name> \"x\" .\n",
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    );
+    assert_eq!(
+        "<http://example.org/a%20b>",
+        Iri::new("http://example.org/a b").to_string()
+    );
+}
+
+#[test]
+fn test_template_href() {
+    use crate::{RdfaGraph, RdfaProcessor};
+    let html = r#"<div about="me">
+                    <a rel="http://schema.org/url" href="http://ex.org/{x}|y">link</a>
+                    <a rel="http://schema.org/sameAs" href="/{id}">other</a>
+                  </div>"#;
+    let expected =
+        "<http://example.org/me> <http://schema.org/sameAs> <http://example.org/%7Bid%7D> .
+<http://example.org/me> <http://schema.org/url> <http://ex.org/%7Bx%7D%7Cy> .
+";
+    let graph = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap()
+        .parse_str(html)
+        .unwrap();
+    assert_eq!(expected, graph.to_string());
+    assert_eq!(
+        expected,
+        RdfaGraph::parse_str(html, "http://example.org/", None).unwrap()
+    );
+}
+
+#[test]
+fn test_rdf_xml() {
+    use crate::{
+        Iri, Literal, RdfaError, RdfaProcessor, SequentialIdGenerator, Statement,
+        constants::RDF_XML_LITERAL,
+    };
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .blank_node_ids(SequentialIdGenerator::default)
+        .build()
+        .unwrap();
+    let html = r#"
+        <div prefix="ex: http://example.org/ns#" about="me" typeof="foaf:Person">
+          <p property="foaf:name" lang="en">Amanda &amp; co</p>
+          <p property="foaf:age" datatype="xsd:integer">3</p>
+          <p property="ex:bio" datatype="rdf:XMLLiteral">I <em>like</em> graphs</p>
+          <p property="http://example.org/other/name">not a known prefix</p>
+          <div rel="foaf:knows" resource="_:ivan"></div>
+        </div>
+    "#;
+    let mut graph = processor.parse_str(html).unwrap();
+    let xml = graph.to_rdf_xml().unwrap();
+    assert_eq!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<rdf:RDF
+    xmlns:ex="http://example.org/ns#"
+    xmlns:ns1="http://example.org/other/"
+    xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+    xmlns:foaf="http://xmlns.com/foaf/0.1/">
+  <rdf:Description rdf:about="http://example.org/me">
+    <rdf:type rdf:resource="http://xmlns.com/foaf/0.1/Person"/>
+    <ex:bio rdf:parseType="Literal">I <em xmlns="http://www.w3.org/1999/xhtml">like</em> graphs</ex:bio>
+    <ns1:name>not a known prefix</ns1:name>
+    <foaf:age rdf:datatype="http://www.w3.org/2001/XMLSchema#integer">3</foaf:age>
+    <foaf:knows rdf:nodeID="bivan"/>
+    <foaf:name xml:lang="en">Amanda &amp; co</foaf:name>
+  </rdf:Description>
+</rdf:RDF>
+"#,
+        xml
+    );
+    assert_eq!(rdf_xml_statements(&graph), read_rdf_xml(&xml));
+
+    // markup that is not well-formed is written as text
+    let me = Iri::new("http://example.org/me");
+    graph.statements.insert(Statement::new(
+        me.clone(),
+        Iri::new("http://example.org/ns#note"),
+        Literal::typed("a < b", Iri::new(RDF_XML_LITERAL)),
+    ));
+    let xml = graph.to_rdf_xml().unwrap();
+    assert!(xml.contains(
+        r#"<ex:note rdf:datatype="http://www.w3.org/1999/02/22-rdf-syntax-ns#XMLLiteral">a &lt; b</ex:note>"#
+    ));
+    assert_eq!(rdf_xml_statements(&graph), read_rdf_xml(&xml));
+
+    // a predicate without a local name
+    graph.statements.insert(Statement::new(
+        me,
+        Iri::new("http://example.org/other/"),
+        Literal::new("no QName"),
+    ));
+    assert!(matches!(
+        graph.to_rdf_xml(),
+        Err(RdfaError::InvalidIri { value, .. }) if value == "http://example.org/other/"
+    ));
+}
+
+/// Statements of the graph, with the blank node ids written by `to_rdf_xml`.
+fn rdf_xml_statements(graph: &crate::RdfaGraph) -> std::collections::HashSet<String> {
+    use crate::{BlankNode, Statement, Term};
+    let node_id = |term: &Term| -> Term<'static> {
+        match term {
+            Term::BlankNode(bnode) => BlankNode::new(format!("b{}", bnode.id())).into(),
+            term => term.clone().into_owned(),
+        }
+    };
+    graph
+        .statements
+        .iter()
+        .map(|stmt| {
+            Statement::new(
+                node_id(&stmt.subject),
+                stmt.predicate.clone(),
+                node_id(&stmt.object),
+            )
+            .to_string()
+        })
+        .collect()
+}
+
+/// Statements of the subset of RDF/XML written by `to_rdf_xml`.
+fn read_rdf_xml(xml: &str) -> std::collections::HashSet<String> {
+    use crate::{BlankNode, Iri, Literal, Statement, Term, constants::RDF_XML_LITERAL};
+    const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+    let document = roxmltree::Document::parse(xml).unwrap();
+    let resource = |node: roxmltree::Node, about: &str| -> Option<Term> {
+        match (
+            node.attribute((RDF, about)),
+            node.attribute((RDF, "nodeID")),
+        ) {
+            (Some(iri), _) => Some(Iri::new(iri.to_string()).into()),
+            (None, Some(id)) => Some(BlankNode::new(id.to_string()).into()),
+            (None, None) => None,
+        }
+    };
+    let mut statements = std::collections::HashSet::new();
+    for description in document
+        .root_element()
+        .children()
+        .filter(|n| n.is_element())
+    {
+        let subject = resource(description, "about").unwrap();
+        for property in description.children().filter(|n| n.is_element()) {
+            let name = property.tag_name();
+            let predicate = Iri::new(format!("{}{}", name.namespace().unwrap(), name.name()));
+            let text = property.text().unwrap_or_default().to_string();
+            let object = if let Some(object) = resource(property, "resource") {
+                object
+            } else if property.attribute((RDF, "parseType")) == Some("Literal") {
+                let content = property
+                    .children()
+                    .map(|c| c.range())
+                    .reduce(|a, b| a.start..b.end);
+                let content = content.map(|range| &xml[range]).unwrap_or_default();
+                Literal::typed(content.to_string(), Iri::new(RDF_XML_LITERAL)).into()
+            } else if let Some(datatype) = property.attribute((RDF, "datatype")) {
+                Literal::typed(text, Iri::new(datatype.to_string())).into()
+            } else if let Some(lang) =
+                property.attribute(("http://www.w3.org/XML/1998/namespace", "lang"))
+            {
+                Literal::lang_tagged(text, lang.to_string()).into()
+            } else {
+                Literal::new(text).into()
+            };
+            statements.insert(Statement::new(subject.clone(), predicate, object).to_string());
+        }
+    }
+    statements
+}
+
+#[test]
+fn test_terms() {
+    use crate::{BlankNode, Iri, Literal, RdfaProcessor, Statement, Term};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap();
+    // the same triple from a term, a CURIE and an IRI
+    let html = r#"
+        <div vocab="http://schema.org/" prefix="s: http://schema.org/" about="me">
+          <span property="name">Nordine</span>
+          <span property="s:name">Nordine</span>
+          <span property="http://schema.org/name">Nordine</span>
+          <span property="http://schema.org/name" datatype="xsd:string">Nordine</span>
+        </div>
+    "#;
+    let graph = processor.parse_str(html).unwrap();
+    let name = Statement::new(
+        Iri::new("http://example.org/me"),
+        Iri::new("http://schema.org/name"),
+        Literal::new("Nordine"),
+    );
+    assert!(graph.statements.contains(&name));
+    assert_eq!(
+        2,
+        graph.statements.len(),
+        "rdfa:usesVocabulary and schema:name"
+    );
+    assert_eq!(
+        r#"<http://example.org/me> <http://schema.org/name> "Nordine" ."#,
+        name.to_string()
+    );
+
+    let literal = Literal::typed("1", Iri::new("http://www.w3.org/2001/XMLSchema#integer"));
+    assert_eq!(
+        "\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+        literal.to_string()
+    );
+    assert_eq!(
+        "\"chat\"@fr",
+        Literal::lang_tagged("chat", "fr").to_string()
+    );
+    assert_eq!(Literal::new("chat"), Literal::lang_tagged("chat", ""));
+    let bnode = Term::from(BlankNode::new("b0"));
+    assert_eq!("_:b0", bnode.to_string());
+    assert_eq!(Some("b0"), bnode.as_blank_node().map(BlankNode::id));
+    assert!(bnode.as_iri().is_none());
+}
+
+#[test]
+fn test_triples_matching() {
+    use crate::{Iri, Literal, RdfaProcessor, Term};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .prefix("besluit", "http://data.vlaanderen.be/ns/besluit#")
+        .prefix("dct", "http://purl.org/dc/terms/")
+        .build()
+        .unwrap();
+    let html = r#"
+        <div about="besluit/1" typeof="besluit:Besluit">
+          <h1 property="dct:title">Aanstelling</h1>
+          <p property="dct:subject" resource="topic/1"></p>
+        </div>
+        <div about="besluit/2" typeof="besluit:Besluit">
+          <h1 property="dct:title" lang="nl">Ontslag</h1>
+          <p property="dct:subject" resource="topic/1"></p>
+        </div>
+    "#;
+    let mut graph = processor.parse_str(html).unwrap();
+    let iri = |iri: &'static str| Term::Iri(Iri::new(iri));
+    let (besluit_1, besluit_2) = (
+        iri("http://example.org/besluit/1"),
+        iri("http://example.org/besluit/2"),
+    );
+    let (title, subject, topic) = (
+        iri("http://purl.org/dc/terms/title"),
+        iri("http://purl.org/dc/terms/subject"),
+        iri("http://example.org/topic/1"),
+    );
+
+    assert_eq!(
+        vec![&besluit_1, &besluit_2],
+        graph
+            .subjects_of_type(&iri("http://data.vlaanderen.be/ns/besluit#Besluit"))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![&besluit_1, &besluit_2],
+        graph.subjects(&subject, &topic).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(&Literal::new("Aanstelling")),
+        graph.value(&besluit_1, &title)
+    );
+    assert_eq!(
+        Some(&Literal::lang_tagged("Ontslag", "nl")),
+        graph.value(&besluit_2, &title)
+    );
+    assert_eq!(
+        vec![&topic],
+        graph.objects(&besluit_1, &subject).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        1,
+        graph
+            .triples_matching(Some(&besluit_1), None, Some(&topic))
+            .count()
+    );
+    assert_eq!(
+        1,
+        graph
+            .triples_matching(Some(&besluit_2), Some(&subject), Some(&topic))
+            .count()
+    );
+    assert_eq!(
+        0,
+        graph
+            .triples_matching(Some(&besluit_1), Some(&title), Some(&topic))
+            .count()
+    );
+    assert_eq!(6, graph.triples_matching(None, None, None).count());
+
+    // same answers from the index
+    let index = graph.index();
+    assert_eq!(
+        graph
+            .subjects_of_type(&iri("http://data.vlaanderen.be/ns/besluit#Besluit"))
+            .collect::<Vec<_>>(),
+        index
+            .subjects_of_type(&iri("http://data.vlaanderen.be/ns/besluit#Besluit"))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        graph.value(&besluit_2, &title),
+        index.value(&besluit_2, &title)
+    );
+    assert_eq!(
+        1,
+        index
+            .triples_matching(Some(&besluit_1), None, Some(&topic))
+            .count()
+    );
+    assert_eq!(
+        0,
+        index
+            .triples_matching(Some(&besluit_1), Some(&title), Some(&topic))
+            .count()
+    );
+    assert_eq!(
+        graph.triples_matching(None, None, None).collect::<Vec<_>>(),
+        index.triples_matching(None, None, None).collect::<Vec<_>>()
+    );
+
+    // queries see the changes of the graph
+    let removed = graph
+        .triples_matching(Some(&besluit_1), Some(&title), None)
+        .next()
+        .cloned()
+        .unwrap();
+    graph.statements.remove(&removed);
+    assert_eq!(None, graph.value(&besluit_1, &title));
+    assert_eq!(5, graph.index().triples_matching(None, None, None).count());
+    graph.statements.clear();
+    assert_eq!(0, graph.triples_matching(None, None, None).count());
+}
+
+#[test_case("examples/earl_html5", "example0088" ; "nested blank nodes")]
+#[test_case("examples/earl_html5", "example0218" ; "inlist")]
+#[test_case("examples/earl_html5", "example0321" ; "rdfa copy")]
+#[test_case("examples/other", "example0005" ; "prefixes")]
+fn test_is_isomorphic(input_dir: &str, test_name: &str) {
+    use crate::{PrefixedIdGenerator, RdfaProcessor};
+    let html = std::fs::read_to_string(format!("{input_dir}/{test_name}.html")).unwrap();
+    let parse = |prefix: &'static str| {
+        RdfaProcessor::builder()
+            .base("http://rdfa.info/test-suite/test-cases/rdfa1.1/html5/")
+            .blank_node_ids(move || PrefixedIdGenerator::new(prefix))
+            .build()
+            .unwrap()
+            .parse_str(&html)
+            .unwrap()
+            .into_owned()
+    };
+    let (graph, other) = (parse("a"), parse("b"));
+    assert!(graph.is_isomorphic(&other));
+    assert!(graph.diff(&other).is_empty());
+}
+
+#[test]
+fn test_isomorphism_of_symmetric_blank_nodes() {
+    use crate::RdfaProcessor;
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .prefix("ex", "http://example.org/ns#")
+        .build()
+        .unwrap();
+    let cycle = |edges: &[(&str, &str)]| {
+        let html = edges
+            .iter()
+            .map(|(s, o)| format!(r#"<p about="_:{s}" rel="ex:next" resource="_:{o}"></p>"#))
+            .collect::<String>();
+        processor.parse_str(&html).unwrap().into_owned()
+    };
+    let hexagon = cycle(&[
+        ("a", "b"),
+        ("b", "c"),
+        ("c", "d"),
+        ("d", "e"),
+        ("e", "f"),
+        ("f", "a"),
+    ]);
+    let rotated = cycle(&[
+        ("f", "c"),
+        ("c", "d"),
+        ("d", "a"),
+        ("a", "e"),
+        ("e", "b"),
+        ("b", "f"),
+    ]);
+    let triangles = cycle(&[
+        ("a", "b"),
+        ("b", "c"),
+        ("c", "a"),
+        ("d", "e"),
+        ("e", "f"),
+        ("f", "d"),
+    ]);
+    assert!(hexagon.is_isomorphic(&rotated));
+    // every blank node looks the same, only trying the mappings tells them apart
+    assert!(!hexagon.is_isomorphic(&triangles));
+    assert!(!triangles.is_isomorphic(&hexagon));
+}
+
+#[test]
+fn test_diff() {
+    use crate::{Iri, Literal, RdfaProcessor, Statement};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap();
+    let graph = processor
+        .parse_str(
+            r#"
+            <div about="me" typeof="foaf:Person">
+              <span property="foaf:name">Nordine</span>
+              <div rel="foaf:knows"><span typeof="foaf:Person" property="foaf:name">Ivan</span></div>
+            </div>
+            "#,
+        )
+        .unwrap();
+    let other = processor
+        .parse_str(
+            r#"
+            <div about="me" typeof="foaf:Person">
+              <span property="foaf:nick">nb</span>
+              <div rel="foaf:knows"><span typeof="foaf:Person" property="foaf:name">Ivan</span></div>
+            </div>
+            "#,
+        )
+        .unwrap();
+    assert!(!graph.is_isomorphic(&other));
+    let diff = graph.diff(&other);
+    let me = Iri::new("http://example.org/me");
+    assert_eq!(
+        vec![Statement::new(
+            me.clone(),
+            Iri::new("http://xmlns.com/foaf/0.1/nick"),
+            Literal::new("nb")
+        )],
+        diff.added
+    );
+    assert_eq!(
+        vec![Statement::new(
+            me,
+            Iri::new("http://xmlns.com/foaf/0.1/name"),
+            Literal::new("Nordine")
+        )],
+        diff.removed
+    );
+}
+
+#[test]
+fn test_diff_of_symmetric_blank_nodes() {
+    use crate::{Iri, Literal, RdfaProcessor, Statement};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .prefix("ex", "http://example.org/ns#")
+        .build()
+        .unwrap();
+    let graph = |edges: &[(&str, &str)], title: &str| {
+        let html = edges
+            .iter()
+            .map(|(s, o)| format!(r#"<p about="_:{s}" rel="ex:next" resource="_:{o}"></p>"#))
+            .chain([format!(r#"<p about="doc" property="ex:title">{title}</p>"#)])
+            .collect::<String>();
+        processor.parse_str(&html).unwrap().into_owned()
+    };
+    // the same two cycles, sorting their ids does not pair them
+    let graph_1 = graph(&[("a", "c"), ("c", "a"), ("b", "d"), ("d", "b")], "v1");
+    let graph_2 = graph(&[("a", "b"), ("b", "a"), ("c", "d"), ("d", "c")], "v2");
+    let diff = graph_1.diff(&graph_2);
+    let title = |title| {
+        Statement::new(
+            Iri::new("http://example.org/doc"),
+            Iri::new("http://example.org/ns#title"),
+            Literal::new(title),
+        )
+    };
+    assert_eq!(vec![title("v2")], diff.added);
+    assert_eq!(vec![title("v1")], diff.removed);
+}
+
+#[test]
+fn test_vocabulary_expansion() {
+    use std::collections::HashMap;
+
+    use crate::{DirectoryResolver, RdfaProcessor, Statement, VocabularyResolver};
+    let html = r#"
+        <div vocab="http://example.org/vocab#" about="me" typeof="Person">
+          <span property="nickname">nb</span>
+        </div>
+    "#;
+    let expected = r#"<http://example.org/me> <http://example.org/vocab#nickname> "nb" .
+<http://example.org/me> <http://example.org/vocab#name> "nb" .
+<http://example.org/me> <http://www.w3.org/2000/01/rdf-schema#label> "nb" .
+<http://example.org/me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/vocab#Person> .
+<http://example.org/me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/vocab#Agent> .
+<http://example.org/me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://xmlns.com/foaf/0.1/Person> .
+<http://example.org/> <http://www.w3.org/ns/rdfa#usesVocabulary> <http://example.org/vocab#> ."#;
+    let mut expected = expected.lines().collect::<Vec<_>>();
+    expected.sort();
+    let parse = |processor: RdfaProcessor| {
+        let graph = processor.parse_str(html).unwrap().to_string();
+        let mut lines = graph.lines().map(String::from).collect::<Vec<_>>();
+        lines.sort();
+        lines
+    };
+
+    assert_eq!(
+        "http%3A%2F%2Fexample.org%2Fvocab%23.html",
+        DirectoryResolver::file_name("http://example.org/vocab#")
+    );
+    // vocabularies that only differ by their punctuation have their own file
+    assert_ne!(
+        DirectoryResolver::file_name("http://example.org/vocab#"),
+        DirectoryResolver::file_name("http://example.org/vocab/")
+    );
+    assert_ne!(
+        DirectoryResolver::file_name("http://example.org/a_b"),
+        DirectoryResolver::file_name("http://example.org/a%5Fb")
+    );
+
+    let directory = DirectoryResolver::new("examples/vocabularies");
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .vocabulary_expansion(directory.clone())
+        .build()
+        .unwrap();
+    assert_eq!(expected, parse(processor.clone()));
+
+    // the triples of the document are streamed, the entailed ones come last
+    let document = scraper::Html::parse_document(html);
+    let mut streamed: Vec<Statement> = vec![];
+    processor
+        .parse_into(&document.root_element(), &mut streamed)
+        .unwrap();
+    assert_eq!(7, streamed.len());
+    assert!(
+        streamed[..3]
+            .iter()
+            .all(|stmt| !stmt.to_string().contains("#name>") && !stmt.to_string().contains("Agent"))
+    );
+
+    let in_memory = HashMap::from([(
+        "http://example.org/vocab#".to_string(),
+        directory.resolve("http://example.org/vocab#").unwrap(),
+    )]);
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .vocabulary_expansion(in_memory)
+        .build()
+        .unwrap();
+    assert_eq!(expected, parse(processor));
+
+    // unknown vocabularies are not expanded
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .vocabulary_expansion(HashMap::<String, Vec<Statement<'static>>>::new())
+        .build()
+        .unwrap();
+    assert_eq!(3, parse(processor).len());
+}
+
+#[test_case(HostLanguage::Html5, "http://example.org/", Some("en"), true, false ; "html5")]
+#[test_case(HostLanguage::Xhtml5, "http://example.org/xml-base/", Some("nl"), true, false ; "xhtml5")]
+#[test_case(HostLanguage::Html4, "http://example.org/", Some("en"), false, false ; "html4")]
+#[test_case(HostLanguage::Xhtml1, "http://example.org/", Some("nl"), false, true ; "xhtml1")]
+#[test_case(HostLanguage::Svg, "http://example.org/xml-base/", Some("nl"), false, false ; "svg")]
+#[test_case(HostLanguage::Xml, "http://example.org/xml-base/", Some("nl"), false, false ; "xml")]
+fn test_host_language(
+    host_language: HostLanguage,
+    subject: &str,
+    lang: Option<&str>,
+    typed_datetime: bool,
+    xhtml_terms: bool,
+) {
+    use crate::{Iri, Literal, RdfaProcessor, Term};
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .host_language(host_language)
+        .build()
+        .unwrap();
+    let graph = processor
+        .parse_str(
+            r#"
+            <html>
+              <body xml:base="http://example.org/xml-base/" about="">
+                <p property="dc:title" lang="en" xml:lang="nl">Title</p>
+                <time property="dc:date">2012-03-18</time>
+                <a rel="next" href="http://example.org/next">Next</a>
+              </body>
+            </html>
+            "#,
+        )
+        .unwrap();
+    let subject = Term::Iri(Iri::new(subject));
+    let title = graph.value(
+        &subject,
+        &Term::Iri(Iri::new("http://purl.org/dc/terms/title")),
+    );
+    let expected = match lang {
+        Some(lang) => Literal::lang_tagged("Title", lang),
+        None => Literal::new("Title"),
+    };
+    assert_eq!(Some(&expected), title);
+    let date = graph
+        .value(
+            &subject,
+            &Term::Iri(Iri::new("http://purl.org/dc/terms/date")),
+        )
+        .unwrap();
+    assert_eq!(typed_datetime, date.datatype().is_some());
+    // the initial context of the host language
+    assert_eq!(
+        xhtml_terms,
+        graph
+            .objects(
+                &subject,
+                &Term::Iri(Iri::new("http://www.w3.org/1999/xhtml/vocab#next"))
+            )
+            .next()
+            .is_some()
+    );
+}
+
+#[test]
+fn test_parse_xml() {
+    use crate::{Iri, Literal, RdfaError, RdfaProcessor, Term};
+    let iri = |iri: &'static str| Term::Iri(Iri::new(iri));
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .host_language(HostLanguage::Svg)
+        .build()
+        .unwrap();
+    let graph = processor
+        .parse_xml_str(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
+            <svg xmlns="http://www.w3.org/2000/svg" xmlns:dc="http://purl.org/dc/elements/1.1/"
+                 xml:base="http://example.org/diagrams/" xml:lang="fr">
+              <metadata about="flow.svg" typeof="foaf:Image">
+                <g xmlns:cc="http://creativecommons.org/ns#">
+                  <desc property="dc:title">Diagramme</desc>
+                  <desc property="cc:license" resource="http://creativecommons.org/licenses/by/4.0/"/>
+                  <desc property="dc:description" datatype="rdf:XMLLiteral"><b>bold</b> text</desc>
+                </g>
+              </metadata>
+            </svg>"#,
+        )
+        .unwrap();
+    let image = iri("http://example.org/diagrams/flow.svg");
+    assert_eq!(
+        Some(&Literal::lang_tagged("Diagramme", "fr")),
+        graph.value(&image, &iri("http://purl.org/dc/elements/1.1/title"))
+    );
+    assert_eq!(
+        vec![&iri("http://creativecommons.org/licenses/by/4.0/")],
+        graph
+            .objects(&image, &iri("http://creativecommons.org/ns#license"))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![&image],
+        graph
+            .subjects_of_type(&iri("http://xmlns.com/foaf/0.1/Image"))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(r#"<b xmlns="http://www.w3.org/2000/svg">bold</b> text"#),
+        graph
+            .value(&image, &iri("http://purl.org/dc/elements/1.1/description"))
+            .map(Literal::value)
+    );
+    assert_eq!(
+        Some(&"http://purl.org/dc/elements/1.1/".into()),
+        graph.prefixes.get("dc")
+    );
+
+    // parsed as HostLanguage::Xml: no xhtml terms, the @prefix of <entry> is not
+    // overridden by the xmlns:dc of <feed>, the scope of <entry> is made of both
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/feed")
+        .build()
+        .unwrap();
+    let graph = processor
+        .parse_xml_str(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/elements/1.1/">
+              <entry xmlns:sioc="http://rdfs.org/sioc/ns#" prefix="dc: http://purl.org/dc/terms/"
+                     about="http://example.org/posts/1" typeof="sioc:Post">
+                <title property="dc:title">First post</title>
+                <link rel="sioc:reply_of" href="http://example.org/posts/0"/>
+                <link rel="next" href="http://example.org/posts/2"/>
+              </entry>
+            </feed>"#,
+        )
+        .unwrap();
+    let post = iri("http://example.org/posts/1");
+    assert_eq!(
+        Some(&Literal::new("First post")),
+        graph.value(&post, &iri("http://purl.org/dc/terms/title"))
+    );
+    assert_eq!(
+        vec![&iri("http://example.org/posts/0")],
+        graph
+            .objects(&post, &iri("http://rdfs.org/sioc/ns#reply_of"))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(3, graph.statements.len());
+
+    let err = processor.parse_xml_str("<feed><entry></feed>").unwrap_err();
+    assert!(matches!(err, RdfaError::InvalidXml { .. }));
+}
+
+#[test]
+fn test_rdfa_10() {
+    use crate::{RdfaProcessor, RdfaVersion, SequentialIdGenerator};
+    let html = r##"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML+RDFa 1.0//EN" "http://www.w3.org/MarkUp/DTD/xhtml-rdfa-1.dtd">
+        <html xmlns="http://www.w3.org/1999/xhtml" version="XHTML+RDFa 1.0"
+              xmlns:foaf="http://xmlns.com/foaf/0.1/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <body>
+            <div about="#me" typeof="foaf:Person" vocab="http://schema.org/">
+              <span property="foaf:name">Me</span>
+              <a rel="foaf:knows" href="#you">You</a>
+              <ul rel="foaf:interest" inlist=""><li><a href="#rdfa">RDFa</a></li></ul>
+            </div>
+            <a property="dc:title" href="http://example.org/page">Page</a>
+            <span typeof="foaf:Person" property="foaf:name">Dan</span>
+            <p about="#me" property="dc:description">Some <em>text</em></p>
+            <img src="photo.jpg" rel="dc:creator" resource="#me" />
+            <p prefix="ex: http://example.org/ns#" property="ex:ignored">Ignored</p>
+          </body>
+        </html>"##;
+    let parse = |version: Option<RdfaVersion>| {
+        let mut options = RdfaProcessor::builder()
+            .base("http://example.org/")
+            .host_language(HostLanguage::Xhtml1)
+            .blank_node_ids(SequentialIdGenerator::default);
+        if let Some(version) = version {
+            options = options.version(version);
+        }
+        let mut triples = options
+            .build()
+            .unwrap()
+            .parse_str(html)
+            .unwrap()
+            .to_string()
+            .lines()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        triples.sort();
+        triples
+    };
+    let expected = [
+        r#"<http://example.org/#me> <http://purl.org/dc/elements/1.1/description> "Some <em xmlns=\"http://www.w3.org/1999/xhtml\">text</em>"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#XMLLiteral> ."#,
+        "<http://example.org/#me> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://xmlns.com/foaf/0.1/Person> .",
+        "<http://example.org/#me> <http://xmlns.com/foaf/0.1/interest> <http://example.org/#rdfa> .",
+        "<http://example.org/#me> <http://xmlns.com/foaf/0.1/knows> <http://example.org/#you> .",
+        r#"<http://example.org/#me> <http://xmlns.com/foaf/0.1/name> "Me" ."#,
+        r#"<http://example.org/page> <http://purl.org/dc/elements/1.1/title> "Page" ."#,
+        "<http://example.org/photo.jpg> <http://purl.org/dc/elements/1.1/creator> <http://example.org/#me> .",
+        "_:1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://xmlns.com/foaf/0.1/Person> .",
+        r#"_:1 <http://xmlns.com/foaf/0.1/name> "Dan" ."#,
+    ];
+    let detected = parse(None);
+    assert_eq!(expected.to_vec(), detected);
+    // @prefix is ignored, the CURIE cannot be resolved
+    assert!(!detected.iter().any(|triple| triple.contains("ignored")));
+    assert_eq!(detected, parse(Some(RdfaVersion::Rdfa10)));
+    assert_ne!(detected, parse(Some(RdfaVersion::Rdfa11)));
+}
+
+#[test]
+fn test_xml_literal_canonicalization() {
+    use crate::{Iri, RdfaProcessor, Term};
+    let description = |graph: &crate::RdfaGraph| {
+        graph
+            .value(
+                &Term::Iri(Iri::new("http://example.org/me")),
+                &Term::Iri(Iri::new("http://purl.org/dc/terms/description")),
+            )
+            .map(|l| l.value().to_string())
+            .unwrap()
+    };
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .host_language(HostLanguage::Xml)
+        .build()
+        .unwrap();
+    let graph = processor
+        .parse_xml_str(
+            r##"<doc xmlns="http://example.org/doc" xmlns:m="http://www.w3.org/1998/Math/MathML" xmlns:unused="http://example.org/unused" xmlns:xl="http://www.w3.org/1999/xlink">
+              <p about="http://example.org/me" property="dc:description" datatype="rdf:XMLLiteral"><!-- note -->a &lt; b &amp;&gt; c<br/><m:math display='block' class="x &quot;y&quot;&#9;"><m:mi xmlns:x="http://example.org/x">x</m:mi><m:mo xl:href="#plus">+</m:mo><m:mtext><em>y</em></m:mtext></m:math><?pi data?></p>
+            </doc>"##,
+        )
+        .unwrap();
+    // only the namespaces visibly utilized, declared where they are used first
+    assert_eq!(
+        concat!(
+            "<!-- note -->a &lt; b &amp;&gt; c",
+            r#"<br xmlns="http://example.org/doc"></br>"#,
+            r#"<m:math xmlns:m="http://www.w3.org/1998/Math/MathML" class="x &quot;y&quot;&#x9;" display="block">"#,
+            "<m:mi>x</m:mi>",
+            r##"<m:mo xmlns:xl="http://www.w3.org/1999/xlink" xl:href="#plus">+</m:mo>"##,
+            r#"<m:mtext><em xmlns="http://example.org/doc">y</em></m:mtext>"#,
+            "</m:math><?pi data?>"
+        ),
+        description(&graph)
+    );
+
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap();
+    let graph = processor
+        .parse_str(
+            r#"<p about="me" property="dc:description" datatype="rdf:XMLLiteral">E = mc<sup title="a>b">2</sup><br><svg><circle r="1"/></svg></p>"#,
+        )
+        .unwrap();
+    assert_eq!(
+        concat!(
+            r#"E = mc<sup xmlns="http://www.w3.org/1999/xhtml" title="a>b">2</sup>"#,
+            r#"<br xmlns="http://www.w3.org/1999/xhtml"></br>"#,
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><circle r="1"></circle></svg>"#
+        ),
+        description(&graph)
+    );
+}
+
+#[test]
+fn test_curie_grammar() {
+    use crate::RdfaProcessor;
+    let html = r#"
+        <div prefix="EX: http://example.org/ns# 2ex: http://example.org/invalid# _: http://example.org/blank#"
+             about="http://example.org/me">
+          <p property="ex:name Ex:nickname">Amanda</p>
+          <p property="ex::colon">Colon</p>
+          <a rel="ex:mbox" href="mailto:amanda@example.org">Mail</a>
+          <a rel="ex:isbn" resource="urn:isbn:0451450523">Book</a>
+          <a rel="ex:page" resource="ex://example.org/page">Page</a>
+          <a rel="ex:safe" href="[ex:safe]">Safe</a>
+          <a rel="ex:blank" resource="[_:b]">Blank</a>
+        </div>
+    "#;
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .processor_graph(true)
+        .build()
+        .unwrap();
+    let graph = processor.parse_str(html).unwrap();
+    let triples = graph.to_string();
+    for triple in [
+        r#"<http://example.org/me> <http://example.org/ns#name> "Amanda" ."#,
+        r#"<http://example.org/me> <http://example.org/ns#nickname> "Amanda" ."#,
+        r#"<http://example.org/me> <http://example.org/ns#:colon> "Colon" ."#,
+        "<http://example.org/me> <http://example.org/ns#mbox> <mailto:amanda@example.org> .",
+        "<http://example.org/me> <http://example.org/ns#isbn> <urn:isbn:0451450523> .",
+        "<http://example.org/me> <http://example.org/ns#page> <ex://example.org/page> .",
+    ] {
+        assert!(triples.contains(triple), "{triple} not in {triples}");
+    }
+    assert!(!triples.contains("http://example.org/invalid#"));
+    assert!(!triples.contains("http://example.org/blank#"));
+    assert!(!triples.contains("http://example.org/ns#safe"));
+    assert!(triples.contains("<http://example.org/ns#blank> _:"));
+
+    let processor_graph = graph.processor_graph.unwrap().to_string();
+    for value in [
+        "2ex: http://example.org/invalid#",
+        "_: http://example.org/blank#",
+        "[ex:safe]",
+    ] {
+        assert!(processor_graph.contains(value), "{value} not reported");
+    }
+
+    assert!(
+        RdfaProcessor::builder()
+            .prefix("2ex", "http://example.org/")
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+fn test_html5_value_elements() {
+    use crate::RdfaProcessor;
+    let html = r#"
+        <div vocab="http://schema.org/" about="http://example.org/concert" lang="nl">
+          <data property="identifier" value="42">Concert forty-two</data>
+          <meter property="ratingValue" value="0.8" min="0" max="1">80%</meter>
+          <data property="sku" value="A-1" datatype="xsd:token">A1</data>
+          <time property="startDate"><b>2024-05-17</b>T20:00:00</time>
+          <time property="doorTime">20:00:00</time>
+          <time property="duration">PT2H30M</time>
+          <time property="endDate">vrijdagavond</time>
+          <time property="dateCreated" datetime="2024">dit jaar</time>
+          <audio property="audio" src="song.mp3"></audio>
+          <video property="video" src="clip.mp4" poster="poster.png"></video>
+          <object property="subjectOf" data="program.pdf" type="application/pdf"></object>
+          <span property="description">Het <time>2024</time> concert</span>
+        </div>
+    "#;
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .build()
+        .unwrap();
+    let graph = processor.parse_str(html).unwrap();
+    let triples = graph.to_string();
+    for (property, object) in [
+        ("identifier", r#""42"@nl"#),
+        ("ratingValue", r#""0.8"@nl"#),
+        ("sku", r#""A-1"^^<http://www.w3.org/2001/XMLSchema#token>"#),
+        (
+            "startDate",
+            r#""2024-05-17T20:00:00"^^<http://www.w3.org/2001/XMLSchema#dateTime>"#,
+        ),
+        (
+            "doorTime",
+            r#""20:00:00"^^<http://www.w3.org/2001/XMLSchema#time>"#,
+        ),
+        (
+            "duration",
+            r#""PT2H30M"^^<http://www.w3.org/2001/XMLSchema#duration>"#,
+        ),
+        ("endDate", r#""vrijdagavond"@nl"#),
+        (
+            "dateCreated",
+            r#""2024"^^<http://www.w3.org/2001/XMLSchema#gYear>"#,
+        ),
+        ("audio", "<http://example.org/song.mp3>"),
+        ("video", "<http://example.org/clip.mp4>"),
+        ("subjectOf", "<http://example.org/program.pdf>"),
+        ("description", r#""Het 2024 concert"@nl"#),
+    ] {
+        let triple =
+            format!("<http://example.org/concert> <http://schema.org/{property}> {object} .");
+        assert!(triples.contains(&triple), "{triple} not in {triples}");
+    }
+
+    // only HTML5 has value elements
+    let processor = RdfaProcessor::builder()
+        .base("http://example.org/")
+        .host_language(HostLanguage::Html4)
+        .build()
+        .unwrap();
+    let triples = processor.parse_str(html).unwrap().to_string();
+    for triple in [
+        r#"<http://schema.org/identifier> "Concert forty-two"@nl ."#,
+        r#"<http://schema.org/endDate> "vrijdagavond"@nl ."#,
+        "<http://schema.org/subjectOf> <http://example.org/program.pdf> .",
+    ] {
+        assert!(triples.contains(triple), "{triple} not in {triples}");
+    }
+}
@@ -1,4 +1,3 @@
-use serial_test::serial;
 use test_case::test_case;
 
 use super::cmp_files;
@@ -27,7 +26,6 @@ const INPUT_OUTPUT_DIR: &str = "examples/rdfa_core";
 #[test_case("example126b" ; "8.4 List Generation                                : rdfa_core_126b")]
 #[test_case("example127"  ; "8.4 List Generation                                : rdfa_core_127 ")]
 #[test_case("example129"  ; "8.4 List Generation                                : rdfa_core_129 ")]
-#[serial]
 fn test(test_name: &str) {
     cmp_files(test_name, INPUT_OUTPUT_DIR, "http://test.org")
 }
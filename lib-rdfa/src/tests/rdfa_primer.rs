@@ -1,4 +1,3 @@
-use serial_test::serial;
 use test_case::test_case;
 
 use super::cmp_files;
@@ -39,7 +38,6 @@ const INPUT_OUTPUT_DIR: &str = "examples/rdfa_primer";
 #[test_case("example44" ; "2.2.3    Alternative for setting the context  : rdfa_primer_44")]
 #[test_case("example45" ; "2.2.4    Alternative for setting the property : rdfa_primer_45")]
 #[test_case("example46" ; "2.2.4    Alternative for setting the property : rdfa_primer_46")]
-#[serial]
 fn test(test_name: &str) {
     cmp_files(test_name, INPUT_OUTPUT_DIR, "http://test.org")
 }
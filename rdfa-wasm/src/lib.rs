@@ -1,6 +1,6 @@
 #![cfg(target_arch = "wasm32")]
 mod utils;
-use graph_rdfa_processor::RdfaGraph;
+use graph_rdfa_processor::{JsonLdForm, RdfaGraph, RdfaProcessor};
 use lol_alloc::{AssumeSingleThreaded, FreeListAllocator};
 use tortank::turtle::turtle_doc::TurtleDoc;
 use wasm_bindgen::prelude::*;
@@ -11,11 +11,26 @@ static ALLOCATOR: AssumeSingleThreaded<FreeListAllocator> =
 #[wasm_bindgen]
 pub fn html_to_rdfa(html: &str, base: &str, well_known_prefix: &str) -> String {
     utils::set_panic_hook();
-    let wkp = {
-        let wkp = well_known_prefix.trim();
-        if wkp.is_empty() { None } else { Some(wkp) }
+    RdfaGraph::parse_str(html, base, wkp(well_known_prefix)).unwrap()
+}
+
+#[wasm_bindgen]
+pub fn html_to_turtle(html: &str, base: &str, well_known_prefix: &str) -> String {
+    utils::set_panic_hook();
+    parse(html, base, well_known_prefix).to_turtle()
+}
+
+#[wasm_bindgen]
+pub fn html_to_json_ld(html: &str, base: &str, well_known_prefix: &str, compacted: bool) -> String {
+    utils::set_panic_hook();
+    let form = if compacted {
+        JsonLdForm::Compacted
+    } else {
+        JsonLdForm::Expanded
     };
-    RdfaGraph::parse_str(html, base, wkp).unwrap()
+    parse(html, base, well_known_prefix)
+        .to_json_ld(form)
+        .to_string()
 }
 
 #[wasm_bindgen]
@@ -24,3 +39,19 @@ pub fn rdfa_to_turtle(rdfa_graph: &str) -> String {
     let turtle_doc = TurtleDoc::try_from((rdfa_graph, None)).unwrap();
     turtle_doc.as_turtle().unwrap()
 }
+
+fn parse(html: &str, base: &str, well_known_prefix: &str) -> RdfaGraph<'static> {
+    let mut options = RdfaProcessor::builder().base(base);
+    if let Some(wkp) = wkp(well_known_prefix) {
+        options = options.well_known_prefix(wkp);
+    }
+    options
+        .build()
+        .and_then(|processor| processor.parse_str(html))
+        .unwrap()
+}
+
+fn wkp(well_known_prefix: &str) -> Option<&str> {
+    let wkp = well_known_prefix.trim();
+    if wkp.is_empty() { None } else { Some(wkp) }
+}